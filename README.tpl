@@ -34,6 +34,10 @@ Licensed under either of
 
 at your option.
 
+The copy of the [Public Suffix List](https://publicsuffix.org/) in `data/public_suffix_list.dat`, which is
+compiled into the crate, is subject to the Mozilla Public License, v. 2.0 ([LICENSE-MPL-2.0](LICENSE-MPL-2.0) or
+https://mozilla.org/MPL/2.0/).
+
 ### Contribution
 
 Unless you explicitly state otherwise, any contribution intentionally submitted for inclusion in the work by you, as defined in the Apache-2.0 license, shall be dual licensed as above, without any additional terms or conditions.
\ No newline at end of file
@@ -1,7 +1,16 @@
 pub mod authority;
 pub mod host_name;
+pub mod host_pattern;
+pub mod origin;
 pub mod path;
+pub mod port;
+pub mod public_suffix;
 pub mod query;
+pub mod relative_ref;
+pub mod router;
 pub mod scheme;
+pub mod segment;
 pub mod uri;
+pub mod uri_pattern;
+pub mod uri_template;
 pub mod user_info;
@@ -1,11 +1,12 @@
 use crate::ast::user_info::UserInfo;
 use std::fmt::Formatter;
 use crate::ast::host_name::HostName;
+use crate::ast::port::{Port, PortError, PortPolicy};
 
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct Authority {
   host_name: HostName,
-  port: Option<u16>,
+  port: Option<Port>,
   user_info: Option<UserInfo>,
 }
 
@@ -35,7 +36,7 @@ impl std::fmt::Display for Authority {
       self.host_name.to_string(),
       self
         .port
-        .map(|n| format!(":{}", n))
+        .map(|p| format!(":{}", p))
         .unwrap_or("".to_string()),
     )
   }
@@ -43,6 +44,11 @@ impl std::fmt::Display for Authority {
 
 impl Authority {
   pub fn new(host_name: HostName, port: Option<u16>, user_info: Option<UserInfo>) -> Self {
+    Self::from_parts(host_name, port.map(Port::Number), user_info)
+  }
+
+  /// Like `new`, but the port may be empty.
+  pub fn from_parts(host_name: HostName, port: Option<Port>, user_info: Option<UserInfo>) -> Self {
     Self {
       host_name,
       port,
@@ -54,11 +60,40 @@ impl Authority {
     &self.host_name
   }
 
+  /// The port number, `None` when the port is absent or empty.
   pub fn port(&self) -> Option<u16> {
+    self.port.and_then(|p| p.number())
+  }
+
+  /// The port as written, distinguishing `h:` from `h`.
+  pub fn port_component(&self) -> Option<Port> {
     self.port
   }
 
+  pub fn has_empty_port(&self) -> bool {
+    self.port == Some(Port::Empty)
+  }
+
+  /// Checks the port against `policy`, dropping an empty port when the policy says so.
+  pub fn apply_port_policy(&self, policy: &PortPolicy) -> Result<Authority, PortError> {
+    let port = policy.apply(self.port)?;
+    Ok(Self::from_parts(
+      self.host_name.clone(),
+      port,
+      self.user_info.clone(),
+    ))
+  }
+
   pub fn user_info(&self) -> Option<&UserInfo> {
     self.user_info.as_ref()
   }
+
+  /// A copy with the password, if any, replaced by `***`.
+  pub fn redacted(&self) -> Authority {
+    Self::from_parts(
+      self.host_name.clone(),
+      self.port,
+      self.user_info.as_ref().map(|ui| ui.redacted()),
+    )
+  }
 }
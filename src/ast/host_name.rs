@@ -1,6 +1,13 @@
 use std::fmt::Formatter;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+/// The longest DNS name in text form, without a trailing dot (RFC 1035, section 3.1).
+pub const MAX_DNS_NAME_LEN: usize = 253;
+
+/// The longest DNS label (RFC 1035, section 2.3.4).
+pub const MAX_DNS_LABEL_LEN: usize = 63;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct HostName(String);
 
 impl Default for HostName {
@@ -31,4 +38,141 @@ impl HostName {
   pub fn new(value: String) -> Self {
     Self(value)
   }
+
+  /// Whether the host is a reg-name rather than an IPv4 address or an IP literal.
+  pub fn is_reg_name(&self) -> bool {
+    !self.0.starts_with('[') && self.0.parse::<Ipv4Addr>().is_err()
+  }
+
+  /// Whether the host is a reg-name that is a valid DNS host name: dot-separated labels of
+  /// letters, digits and hyphens that neither start nor end with a hyphen, each at most
+  /// 63 characters long and at most 253 in total. A trailing dot is allowed.
+  pub fn is_valid_dns_name(&self) -> bool {
+    let name = self.0.strip_suffix('.').unwrap_or(&self.0);
+    self.is_reg_name()
+      && !name.is_empty()
+      && name.len() <= MAX_DNS_NAME_LEN
+      && name.split('.').all(|label| {
+        !label.is_empty()
+          && label.len() <= MAX_DNS_LABEL_LEN
+          && !label.starts_with('-')
+          && !label.ends_with('-')
+          && label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+      })
+  }
+
+  /// The dot-separated labels of a reg-name, without the empty label of a trailing dot.
+  /// Empty for an IP address.
+  pub fn labels(&self) -> Vec<&str> {
+    if !self.is_reg_name() || self.0.is_empty() {
+      return Vec::new();
+    }
+    self
+      .0
+      .strip_suffix('.')
+      .unwrap_or(&self.0)
+      .split('.')
+      .collect()
+  }
+
+  /// The last label of a reg-name.
+  pub fn tld(&self) -> Option<&str> {
+    self.labels().last().copied().filter(|s| !s.is_empty())
+  }
+
+  /// Whether this reg-name lies strictly below `other`, comparing labels case-insensitively,
+  /// so `a.example.com` is a subdomain of `example.com` but `example.com` is not.
+  pub fn is_subdomain_of(&self, other: &HostName) -> bool {
+    let labels = self.labels();
+    let parent = other.labels();
+    !parent.is_empty()
+      && labels.len() > parent.len()
+      && labels
+        .iter()
+        .rev()
+        .zip(parent.iter().rev())
+        .all(|(a, b)| a.eq_ignore_ascii_case(b))
+  }
+
+  /// The address of an IPv4 host or of an `[IPv6]` literal, `None` for a reg-name or an
+  /// IPvFuture literal.
+  pub fn ip_address(&self) -> Option<IpAddr> {
+    match self.0.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+      Some(literal) => literal.parse::<Ipv6Addr>().ok().map(IpAddr::V6),
+      None => self.0.parse::<Ipv4Addr>().ok().map(IpAddr::V4),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::IpAddr;
+
+  use crate::HostName;
+
+  #[test]
+  fn test_is_valid_dns_name() {
+    let valid = |s: &str| HostName::from(s).is_valid_dns_name();
+    let long_label = "a".repeat(63);
+    assert!(valid("example.com"));
+    assert!(valid("example.com."));
+    assert!(valid("xn--bcher-kva.example"));
+    assert!(valid("a-1.B2"));
+    assert!(valid("localhost"));
+    assert!(valid(&format!("{}.com", long_label)));
+    assert!(valid(&format!(
+      "{0}.{0}.{0}.{1}",
+      long_label,
+      "a".repeat(61)
+    )));
+    assert!(!valid(&format!(
+      "{0}.{0}.{0}.{1}",
+      long_label,
+      "a".repeat(62)
+    )));
+    assert!(!valid(&format!("a{}.com", long_label)));
+    assert!(!valid(""));
+    assert!(!valid("."));
+    assert!(!valid("!!!"));
+    assert!(!valid("a..b"));
+    assert!(!valid("-a.com"));
+    assert!(!valid("a-.com"));
+    assert!(!valid("a_b.com"));
+    assert!(!valid("caf%C3%A9.com"));
+    assert!(!valid("192.168.0.1"));
+    assert!(!valid("[::1]"));
+  }
+
+  #[test]
+  fn test_labels() {
+    let host = HostName::from("www.Example.com.");
+    assert_eq!(host.labels(), vec!["www", "Example", "com"]);
+    assert_eq!(host.tld(), Some("com"));
+    assert!(host.is_subdomain_of(&HostName::from("example.COM")));
+    assert!(host.is_subdomain_of(&HostName::from("com")));
+    assert!(!host.is_subdomain_of(&HostName::from("www.example.com")));
+    assert!(!host.is_subdomain_of(&HostName::from("ample.com")));
+    assert!(!host.is_subdomain_of(&HostName::from("")));
+    assert!(HostName::from("127.0.0.1").labels().is_empty());
+    assert_eq!(HostName::from("127.0.0.1").tld(), None);
+    assert_eq!(HostName::from("").tld(), None);
+  }
+
+  #[test]
+  fn test_ip_address() {
+    assert_eq!(
+      HostName::from("127.0.0.1").ip_address(),
+      Some(IpAddr::from([127, 0, 0, 1]))
+    );
+    assert_eq!(
+      HostName::from("[::1]").ip_address(),
+      "::1".parse::<IpAddr>().ok()
+    );
+    assert_eq!(HostName::from("[v1.x]").ip_address(), None);
+    assert_eq!(HostName::from("example.com").ip_address(), None);
+    assert!(!HostName::from("[::1]").is_reg_name());
+    assert!(HostName::from("1.2.3").is_reg_name());
+  }
 }
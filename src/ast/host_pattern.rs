@@ -0,0 +1,267 @@
+use std::fmt::Formatter;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::ast::host_name::HostName;
+use crate::ast::uri::Uri;
+use crate::parser::parsers::{host_parsers, Elms};
+
+/// A pattern that matches hosts, as used by allow-lists and `NO_PROXY`.
+///
+/// - `*` matches every host.
+/// - `example.com` matches that name only.
+/// - `*.example.com` matches names with exactly one more leading label, such as
+///   `www.example.com`, but neither `example.com` nor `a.b.example.com`.
+/// - `.example.com` matches `example.com` and every name below it.
+/// - `10.0.0.0/8`, `fd00::/8` or `[fd00::]/8` match IP hosts in the block; a bare address
+///   such as `127.0.0.1` or `[::1]` matches that address only.
+///
+/// Names compare case-insensitively and ignore a trailing dot.
+///
+/// ```rust
+/// use uri_rs::{HostName, HostPattern};
+/// let pattern: HostPattern = "*.example.com".parse().unwrap();
+/// assert!(pattern.matches(&HostName::from("api.Example.com")));
+/// assert!(!pattern.matches(&HostName::from("example.com")));
+/// let block: HostPattern = "10.0.0.0/8".parse().unwrap();
+/// assert!(block.matches(&HostName::from("10.1.2.3")));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostPattern {
+  Any,
+  Exact(String),
+  Wildcard(String),
+  Suffix(String),
+  Cidr(IpAddr, u8),
+}
+
+/// Error raised for a malformed `HostPattern`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostPatternError {
+  InvalidPattern(String),
+  InvalidPrefixLength(String),
+}
+
+impl std::fmt::Display for HostPatternError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      HostPatternError::InvalidPattern(s) => write!(f, "invalid host pattern: {:?}", s),
+      HostPatternError::InvalidPrefixLength(s) => write!(f, "invalid prefix length: {:?}", s),
+    }
+  }
+}
+
+impl std::error::Error for HostPatternError {}
+
+impl std::fmt::Display for HostPattern {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      HostPattern::Any => write!(f, "*"),
+      HostPattern::Exact(name) => write!(f, "{}", name),
+      HostPattern::Wildcard(name) => write!(f, "*.{}", name),
+      HostPattern::Suffix(name) => write!(f, ".{}", name),
+      HostPattern::Cidr(IpAddr::V4(addr), len) => write!(f, "{}/{}", addr, len),
+      HostPattern::Cidr(IpAddr::V6(addr), len) => write!(f, "[{}]/{}", addr, len),
+    }
+  }
+}
+
+impl FromStr for HostPattern {
+  type Err = HostPatternError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || HostPatternError::InvalidPattern(s.to_string());
+    if s == "*" {
+      return Ok(HostPattern::Any);
+    }
+    let (addr, len) = match s.find('/') {
+      Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+      None => (s, None),
+    };
+    if let Some(ip) = Self::parse_ip(addr) {
+      let max = if ip.is_ipv4() { 32 } else { 128 };
+      let len = match len {
+        Some(len) => u8::from_str(len)
+          .ok()
+          .filter(|len| *len <= max)
+          .ok_or_else(|| HostPatternError::InvalidPrefixLength(s.to_string()))?,
+        None => max,
+      };
+      return Ok(HostPattern::Cidr(ip, len));
+    }
+    if len.is_some() {
+      return Err(invalid());
+    }
+    let (pattern, name): (fn(String) -> HostPattern, &str) =
+      if let Some(name) = s.strip_prefix("*.") {
+        (HostPattern::Wildcard, name)
+      } else if let Some(name) = s.strip_prefix('.') {
+        (HostPattern::Suffix, name)
+      } else {
+        (HostPattern::Exact, s)
+      };
+    let name = Self::normalize(name);
+    match host_parsers::host_name(Elms::new(name.as_bytes())) {
+      Ok((rest, host_name)) if rest.as_str() == Ok("") && host_name.is_reg_name() => {
+        if name.contains('*') || name.split('.').any(|label| label.is_empty()) {
+          Err(invalid())
+        } else {
+          Ok(pattern(name))
+        }
+      }
+      _ => Err(invalid()),
+    }
+  }
+}
+
+impl HostPattern {
+  /// Parses a comma- or whitespace-separated list of patterns, such as the value of
+  /// `NO_PROXY`.
+  pub fn parse_list(s: &str) -> Result<Vec<HostPattern>, HostPatternError> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+      .filter(|s| !s.is_empty())
+      .map(HostPattern::from_str)
+      .collect()
+  }
+
+  pub fn matches(&self, host: &HostName) -> bool {
+    match self {
+      HostPattern::Any => true,
+      HostPattern::Cidr(block, len) => match host.ip_address() {
+        Some(ip) => Self::in_block(&ip, block, *len),
+        None => false,
+      },
+      _ if !host.is_reg_name() => false,
+      HostPattern::Exact(name) => Self::normalize(&host.to_string()) == *name,
+      HostPattern::Wildcard(name) => {
+        let host = Self::normalize(&host.to_string());
+        match host.split_once('.') {
+          Some((label, rest)) => !label.is_empty() && rest == name,
+          None => false,
+        }
+      }
+      HostPattern::Suffix(name) => {
+        let host = Self::normalize(&host.to_string());
+        host == *name || host.ends_with(&format!(".{}", name))
+      }
+    }
+  }
+
+  /// Whether the host of `uri` matches; a URI without an authority matches nothing.
+  pub fn matches_uri(&self, uri: &Uri) -> bool {
+    uri
+      .authority()
+      .map(|authority| self.matches(authority.host_name()))
+      .unwrap_or(false)
+  }
+
+  /// Whether any of `patterns` matches `host`.
+  pub fn matches_any(patterns: &[HostPattern], host: &HostName) -> bool {
+    patterns.iter().any(|pattern| pattern.matches(host))
+  }
+
+  /// The patterns joined with `,`, the inverse of `parse_list`.
+  pub fn join(patterns: &[HostPattern]) -> String {
+    patterns.iter().join(",")
+  }
+
+  fn normalize(name: &str) -> String {
+    name.strip_suffix('.').unwrap_or(name).to_ascii_lowercase()
+  }
+
+  fn parse_ip(s: &str) -> Option<IpAddr> {
+    let s = s
+      .strip_prefix('[')
+      .and_then(|s| s.strip_suffix(']'))
+      .unwrap_or(s);
+    IpAddr::from_str(s).ok()
+  }
+
+  fn in_block(ip: &IpAddr, block: &IpAddr, len: u8) -> bool {
+    match (ip, block) {
+      (IpAddr::V4(ip), IpAddr::V4(block)) => {
+        let mask = u32::MAX.checked_shl(32 - len as u32).unwrap_or(0);
+        u32::from(*ip) & mask == u32::from(*block) & mask
+      }
+      (IpAddr::V6(ip), IpAddr::V6(block)) => {
+        let mask = u128::MAX.checked_shl(128 - len as u32).unwrap_or(0);
+        u128::from(*ip) & mask == u128::from(*block) & mask
+      }
+      _ => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{HostName, HostPattern, HostPatternError, Uri};
+
+  fn matches(pattern: &str, host: &str) -> bool {
+    pattern
+      .parse::<HostPattern>()
+      .unwrap()
+      .matches(&HostName::from(host))
+  }
+
+  #[test]
+  fn test_names() {
+    assert!(matches("example.com", "Example.COM."));
+    assert!(!matches("example.com", "www.example.com"));
+    assert!(matches("*.example.com", "www.example.com"));
+    assert!(!matches("*.example.com", "a.b.example.com"));
+    assert!(!matches("*.example.com", "example.com"));
+    assert!(!matches("*.example.com", "wwwexample.com"));
+    assert!(matches(".example.com", "example.com"));
+    assert!(matches(".example.com", "a.b.example.com"));
+    assert!(!matches(".example.com", "badexample.com"));
+    assert!(matches("*", "anything"));
+    assert!(matches("*", "[::1]"));
+    assert!(!matches("example.com", "[::1]"));
+  }
+
+  #[test]
+  fn test_cidr() {
+    assert!(matches("10.0.0.0/8", "10.255.0.1"));
+    assert!(!matches("10.0.0.0/8", "11.0.0.1"));
+    assert!(matches("0.0.0.0/0", "192.168.1.1"));
+    assert!(matches("127.0.0.1", "127.0.0.1"));
+    assert!(!matches("127.0.0.1", "127.0.0.2"));
+    assert!(matches("fd00::/8", "[fd12::1]"));
+    assert!(matches("[fd00::]/8", "[FD12::1]"));
+    assert!(!matches("fd00::/8", "[fe80::1]"));
+    assert!(matches("[::1]", "[::1]"));
+    assert!(!matches("10.0.0.0/8", "[::ffff:10.0.0.1]"));
+    assert!(!matches("10.0.0.0/8", "ten.example"));
+  }
+
+  #[test]
+  fn test_parse() {
+    assert_eq!(
+      "10.0.0.0/33".parse::<HostPattern>(),
+      Err(HostPatternError::InvalidPrefixLength(
+        "10.0.0.0/33".to_string()
+      ))
+    );
+    for s in [
+      "", ".", "*.", "a..b", "a b", "**.a", "a.*.b", "host/8", "[v1.x]",
+    ]
+    .iter()
+    {
+      assert!(s.parse::<HostPattern>().is_err(), "{}", s);
+    }
+    let patterns = HostPattern::parse_list("localhost, .internal,10.0.0.0/8  [::1]").unwrap();
+    assert_eq!(patterns.len(), 4);
+    assert_eq!(
+      HostPattern::join(&patterns),
+      "localhost,.internal,10.0.0.0/8,[::1]/128"
+    );
+    assert!(HostPattern::matches_any(
+      &patterns,
+      &HostName::from("db.internal")
+    ));
+    assert!(patterns[1].matches_uri(&Uri::parse("http://api.internal:8080/").unwrap()));
+    assert!(!patterns[1].matches_uri(&Uri::parse("urn:isbn:0451450523").unwrap()));
+  }
+}
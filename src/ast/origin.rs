@@ -0,0 +1,165 @@
+use std::fmt::Formatter;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::ast::host_name::HostName;
+use crate::ast::scheme::{KnownScheme, Scheme};
+use crate::ast::uri::Uri;
+
+static NEXT_OPAQUE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The origin of a URI (RFC 6454).
+///
+/// URIs of the `http`, `https`, `ws`, `wss` and `ftp` schemes with a host have a tuple
+/// origin of the lower-cased scheme and host and the effective port. A `blob:` URI has the
+/// origin of the URI it wraps. Every other URI has an opaque origin, which is only equal
+/// to itself: two calls of `Uri::origin` on such a URI give different origins.
+///
+/// ```rust
+/// use uri_rs::Uri;
+/// let origin = Uri::parse("HTTPS://Example.com:443/a?b#c").unwrap().origin();
+/// assert_eq!(origin.ascii_serialization(), "https://example.com");
+/// assert!(origin.is_same_origin(&Uri::parse("https://example.com/other").unwrap()));
+/// assert!(!origin.is_same_origin(&Uri::parse("http://example.com/").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Origin {
+  Tuple {
+    scheme: Scheme,
+    host: HostName,
+    port: Option<u16>,
+  },
+  Opaque(u64),
+}
+
+impl std::fmt::Display for Origin {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.ascii_serialization())
+  }
+}
+
+impl Origin {
+  /// A new opaque origin, different from every other one.
+  pub fn new_opaque() -> Self {
+    Origin::Opaque(NEXT_OPAQUE_ID.fetch_add(1, Ordering::Relaxed))
+  }
+
+  pub fn is_opaque(&self) -> bool {
+    matches!(self, Origin::Opaque(_))
+  }
+
+  /// `scheme://host[:port]`, leaving out the default port of the scheme, or `null` for an
+  /// opaque origin (RFC 6454, section 6.2).
+  pub fn ascii_serialization(&self) -> String {
+    match self {
+      Origin::Tuple { scheme, host, port } => match port {
+        Some(port) if Some(*port) != scheme.default_port() => {
+          format!("{}://{}:{}", scheme, host, port)
+        }
+        _ => format!("{}://{}", scheme, host),
+      },
+      Origin::Opaque(_) => "null".to_string(),
+    }
+  }
+
+  pub fn is_same_origin(&self, uri: &Uri) -> bool {
+    !self.is_opaque() && *self == uri.origin()
+  }
+}
+
+impl Uri {
+  pub fn origin(&self) -> Origin {
+    match self.schema().known() {
+      KnownScheme::Http
+      | KnownScheme::Https
+      | KnownScheme::Ws
+      | KnownScheme::Wss
+      | KnownScheme::Ftp => match self.authority() {
+        Some(authority) if !authority.host_name().to_string().is_empty() => {
+          let scheme = Scheme::from(self.schema().as_lowercase());
+          Origin::Tuple {
+            port: authority.port().or_else(|| scheme.default_port()),
+            scheme,
+            host: HostName::from(authority.host_name().to_string().to_ascii_lowercase()),
+          }
+        }
+        _ => Origin::new_opaque(),
+      },
+      KnownScheme::Blob => match Uri::parse(&self.path().to_string()) {
+        Ok(inner)
+          if matches!(
+            inner.schema().known(),
+            KnownScheme::Http | KnownScheme::Https
+          ) =>
+        {
+          inner.origin()
+        }
+        _ => Origin::new_opaque(),
+      },
+      _ => Origin::new_opaque(),
+    }
+  }
+
+  /// Whether both URIs have the same tuple origin.
+  pub fn is_same_origin(&self, other: &Uri) -> bool {
+    self.origin().is_same_origin(other)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::Uri;
+
+  #[test]
+  fn test_tuple_origin() {
+    let serialize = |s: &str| Uri::parse(s).unwrap().origin().ascii_serialization();
+    assert_eq!(serialize("http://example.com/a"), "http://example.com");
+    assert_eq!(serialize("http://example.com:80/a"), "http://example.com");
+    assert_eq!(serialize("http://example.com:/a"), "http://example.com");
+    assert_eq!(
+      serialize("http://example.com:8080/a"),
+      "http://example.com:8080"
+    );
+    assert_eq!(
+      serialize("https://user:pw@EXAMPLE.com:8443"),
+      "https://example.com:8443"
+    );
+    assert_eq!(serialize("wss://[::1]/socket"), "wss://[::1]");
+    assert_eq!(
+      serialize("blob:https://example.com:443/550e8400"),
+      "https://example.com"
+    );
+  }
+
+  #[test]
+  fn test_opaque_origin() {
+    for s in [
+      "file:///etc/hosts",
+      "data:,hello",
+      "mailto:a@example.com",
+      "urn:isbn:0451450523",
+      "blob:data:,x",
+      "http:/no-host",
+    ]
+    .iter()
+    {
+      let uri = Uri::parse(s).unwrap();
+      let origin = uri.origin();
+      assert!(origin.is_opaque(), "{}", s);
+      assert_eq!(origin.ascii_serialization(), "null");
+      assert_eq!(origin, origin.clone());
+      assert_ne!(origin, uri.origin());
+      assert!(!uri.is_same_origin(&uri));
+    }
+  }
+
+  #[test]
+  fn test_same_origin() {
+    let uri = |s: &str| Uri::parse(s).unwrap();
+    let base = uri("https://example.com/a");
+    assert!(base.is_same_origin(&uri("HTTPS://EXAMPLE.COM:443/b?c#d")));
+    assert!(!base.is_same_origin(&uri("http://example.com/a")));
+    assert!(!base.is_same_origin(&uri("https://example.com:8443/a")));
+    assert!(!base.is_same_origin(&uri("https://www.example.com/a")));
+    assert!(!base.is_same_origin(&uri("wss://example.com/a")));
+  }
+}
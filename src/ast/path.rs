@@ -3,6 +3,8 @@ use std::fmt::Formatter;
 
 use itertools::Itertools;
 
+use crate::ast::segment::Segment;
+
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Path {
   RootlessPath {
@@ -35,8 +37,9 @@ impl Default for Path {
 impl fmt::Display for Path {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     let root = match self {
-      Path::RootlessPath { .. } | Path::NoSchemePath { .. } | Path::EmptyPath { .. } => "",
-      _ => "/",
+      Path::AbsolutePath { .. } => "/",
+      Path::AbemptyPath { parts, .. } if !parts.is_empty() => "/",
+      _ => "",
     };
     write!(f, "{}{}", root, self.parts().join("/"))
   }
@@ -125,6 +128,10 @@ impl Path {
     }
   }
 
+  pub fn segments(&self) -> Vec<Segment> {
+    self.parts().iter().map(Segment::from).collect_vec()
+  }
+
   pub fn is_empty(&self) -> bool {
     self.parts().is_empty()
   }
@@ -146,24 +153,352 @@ impl Path {
   }
 
   pub fn add_part(&mut self, part: String) {
-    let parts_opt = match self {
+    self.push_segment(part)
+  }
+
+  pub fn add_segment(&mut self, segment: Segment) {
+    self.add_part(segment.to_string())
+  }
+
+  pub fn add_parts(&mut self, parts: Vec<String>) {
+    for x in parts {
+      self.add_part(x)
+    }
+  }
+
+  fn parts_mut(&mut self) -> Option<&mut Vec<String>> {
+    match self {
       Path::RootlessPath { parts, .. } => Some(parts),
       Path::AbemptyPath { parts, .. } => Some(parts),
       Path::AbsolutePath { parts, .. } => Some(parts),
       Path::NoSchemePath { parts, .. } => Some(parts),
       Path::EmptyPath { .. } => None,
-    };
-    match parts_opt {
-      Some(parts) => {
-        parts.push(part);
+    }
+  }
+
+  fn with_same_type(&self, parts: Vec<String>) -> Path {
+    match self {
+      Path::RootlessPath { .. } => Path::of_rootless_from_strings(&parts),
+      Path::AbsolutePath { .. } => Path::of_absolute_from_strings(&parts),
+      Path::NoSchemePath { .. } => Path::of_no_scheme_from_strings(&parts),
+      Path::AbemptyPath { .. } | Path::EmptyPath { .. } => Path::of_abempty_from_strings(&parts),
+    }
+  }
+
+  /// Whether the path starts with `/`.
+  pub fn is_rooted(&self) -> bool {
+    match self {
+      Path::AbsolutePath { .. } => true,
+      Path::AbemptyPath { parts, .. } => !parts.is_empty(),
+      _ => false,
+    }
+  }
+
+  /// Appends a segment, replacing a trailing empty segment (`/a/` becomes `/a/b`).
+  ///
+  /// An empty path becomes an abempty path (`/b`), so that it stays valid under an authority.
+  pub fn push_segment(&mut self, segment: String) {
+    if let Path::EmptyPath { .. } = self {
+      *self = Path::of_abempty_from_strs(&[]);
+    }
+    if let Some(parts) = self.parts_mut() {
+      if parts.last().map(|s| s.is_empty()).unwrap_or(false) {
+        parts.pop();
       }
-      None => (),
+      parts.push(segment);
     }
   }
 
-  pub fn add_parts(&mut self, parts: Vec<String>) {
-    for x in parts {
-      self.add_part(x)
+  /// Removes the last segment and returns it, keeping a trailing slash like `push_segment`
+  /// (`/a/b/` becomes `/a/` and gives `b`, `/a/` becomes `/` and gives `a`).
+  pub fn pop_segment(&mut self) -> Option<String> {
+    let parts = self.parts_mut()?;
+    if parts.last().map(|s| s.is_empty()).unwrap_or(false) {
+      parts.pop();
+      let segment = parts.pop();
+      parts.push("".to_string());
+      segment
+    } else {
+      parts.pop()
+    }
+  }
+
+  pub fn last_segment(&self) -> Option<&String> {
+    self.parts().last()
+  }
+
+  /// Returns the path without its last segment, ignoring a trailing slash.
+  ///
+  /// `/a/b/c` and `/a/b/c/` both give `/a/b`, `/a` gives `/`, and `/` gives `None`.
+  pub fn parent(&self) -> Option<Path> {
+    let mut parts = self.parts().clone();
+    if parts.last().map(|s| s.is_empty()).unwrap_or(false) {
+      parts.pop();
+    }
+    parts.pop()?;
+    if parts.is_empty() && self.is_rooted() {
+      if let Path::AbemptyPath { .. } = self {
+        parts.push("".to_string());
+      }
+    }
+    Some(self.with_same_type(parts))
+  }
+
+  /// Returns the extension of the last segment's name, excluding matrix parameters.
+  ///
+  /// A leading dot does not start an extension, so `.htaccess` has none.
+  pub fn extension(&self) -> Option<String> {
+    let segment = Segment::from(self.last_segment()?);
+    let name = segment.name();
+    match name.rfind('.') {
+      Some(idx) if idx > 0 => Some(name[idx + 1..].to_string()),
+      _ => None,
+    }
+  }
+
+  /// Returns a copy with the extension of the last segment replaced.
+  ///
+  /// An empty `extension` removes the current one.
+  pub fn with_extension(&self, extension: &str) -> Path {
+    let mut parts = self.parts().clone();
+    match parts.last_mut() {
+      Some(last) if !last.is_empty() => {
+        let segment = Segment::from(&*last);
+        let name = segment.name();
+        let stem = match name.rfind('.') {
+          Some(idx) if idx > 0 => &name[..idx],
+          _ => name,
+        };
+        let new_name = if extension.is_empty() {
+          stem.to_string()
+        } else {
+          format!("{}.{}", stem, extension)
+        };
+        *last = Segment::new(new_name, segment.params().clone()).to_string();
+        self.with_same_type(parts)
+      }
+      _ => self.clone(),
     }
   }
+
+  /// Joins a path onto this one.
+  ///
+  /// A rooted `relative` replaces this path. Otherwise its segments are pushed one by one.
+  pub fn join(&self, relative: &Path) -> Path {
+    if relative.is_rooted() {
+      match self {
+        Path::AbemptyPath { .. } | Path::EmptyPath { .. } => {
+          Path::of_abempty_from_strings(relative.parts())
+        }
+        _ => relative.clone(),
+      }
+    } else {
+      let mut result = self.clone();
+      for part in relative.parts() {
+        result.push_segment(part.clone());
+      }
+      result
+    }
+  }
+
+  /// Whether `prefix` matches the leading segments of this path.
+  ///
+  /// A trailing slash on `prefix` is ignored, so `/a/` is a prefix of `/a/b`.
+  pub fn starts_with(&self, prefix: &Path) -> bool {
+    self.strip_prefix(prefix).is_some()
+  }
+
+  /// Returns the remaining segments after `prefix` as a relative path.
+  ///
+  /// A `.` segment is put in front when the first remaining segment is empty or contains a
+  /// `:`, so that the result neither reads as a rooted path nor starts with a scheme.
+  pub fn strip_prefix(&self, prefix: &Path) -> Option<Path> {
+    if prefix.is_rooted() != self.is_rooted() && !prefix.to_string().is_empty() {
+      return None;
+    }
+    let mut prefix_parts = prefix.parts().as_slice();
+    if let Some((last, init)) = prefix_parts.split_last() {
+      if last.is_empty() {
+        prefix_parts = init;
+      }
+    }
+    let parts = self.parts();
+    if parts.len() < prefix_parts.len() || &parts[..prefix_parts.len()] != prefix_parts {
+      return None;
+    }
+    let rest = &parts[prefix_parts.len()..];
+    match rest.first() {
+      None => Some(Path::of_empty()),
+      Some(first) if first.is_empty() || first.contains(':') => {
+        let mut parts = vec![".".to_string()];
+        parts.extend(rest.iter().cloned());
+        Some(Path::of_no_scheme_from_strings(&parts))
+      }
+      Some(_) => Some(Path::of_no_scheme_from_strings(rest)),
+    }
+  }
+
+  pub fn has_trailing_slash(&self) -> bool {
+    self.to_string().ends_with('/')
+  }
+}
+
+// RFC 3986 5.2.4. Remove Dot Segments
+pub(crate) fn remove_dot_segments(input: &str) -> String {
+  let mut input = input;
+  let mut output: Vec<&str> = Vec::new();
+  while !input.is_empty() {
+    if input.starts_with("../") {
+      input = &input[3..];
+    } else if input.starts_with("./") || input.starts_with("/./") {
+      input = &input[2..];
+    } else if input == "/." {
+      input = "/";
+    } else if input.starts_with("/../") {
+      input = &input[3..];
+      output.pop();
+    } else if input == "/.." {
+      input = "/";
+      output.pop();
+    } else if input == "." || input == ".." {
+      input = "";
+    } else {
+      let start = if input.starts_with('/') { 1 } else { 0 };
+      let end = input[start..]
+        .find('/')
+        .map(|idx| idx + start)
+        .unwrap_or(input.len());
+      output.push(&input[..end]);
+      input = &input[end..];
+    }
+  }
+  output.concat()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Path, RelativeRef};
+
+  #[test]
+  fn test_push_and_pop_segment() {
+    let mut path = Path::of_empty();
+    path.push_segment("a".to_string());
+    assert_eq!(path.type_name(), "abempty_path");
+    assert_eq!(path.to_string(), "/a");
+    path.push_segment("".to_string());
+    assert_eq!(path.to_string(), "/a/");
+    assert!(path.has_trailing_slash());
+    path.push_segment("b".to_string());
+    assert_eq!(path.to_string(), "/a/b");
+    assert_eq!(path.pop_segment(), Some("b".to_string()));
+    assert_eq!(path.pop_segment(), Some("a".to_string()));
+    assert_eq!(path.pop_segment(), None);
+    assert_eq!(path.to_string(), "");
+
+    let mut path = Path::of_abempty_from_strs(&["a", "b", ""]);
+    assert_eq!(path.pop_segment(), Some("b".to_string()));
+    assert_eq!(path.to_string(), "/a/");
+    assert_eq!(path.pop_segment(), Some("a".to_string()));
+    assert_eq!(path.to_string(), "/");
+    assert_eq!(path.parent(), None);
+    assert_eq!(path.pop_segment(), None);
+    assert_eq!(path.to_string(), "/");
+    path.push_segment("c".to_string());
+    assert_eq!(path.to_string(), "/c");
+
+    let mut path = Path::of_rootless_from_strs(&["a"]);
+    path.add_part("b".to_string());
+    assert_eq!(path.to_string(), "a/b");
+  }
+
+  #[test]
+  fn test_parent() {
+    let path = Path::of_abempty_from_strs(&["a", "b", "c"]);
+    assert_eq!(path.parent().unwrap().to_string(), "/a/b");
+    let path = Path::of_abempty_from_strs(&["a", "b", ""]);
+    assert_eq!(path.parent().unwrap().to_string(), "/a");
+    let path = Path::of_abempty_from_strs(&["a"]);
+    assert_eq!(path.parent().unwrap().to_string(), "/");
+    assert_eq!(path.parent().unwrap().parent(), None);
+    let path = Path::of_absolute_from_strs(&["a"]);
+    assert_eq!(path.parent().unwrap().to_string(), "/");
+    let path = Path::of_rootless_from_strs(&["a", "b"]);
+    assert_eq!(path.parent().unwrap().to_string(), "a");
+    assert_eq!(Path::of_empty().parent(), None);
+  }
+
+  #[test]
+  fn test_extension() {
+    let path = Path::of_abempty_from_strs(&["static", "app.min.js"]);
+    assert_eq!(path.last_segment(), Some(&"app.min.js".to_string()));
+    assert_eq!(path.extension(), Some("js".to_string()));
+    assert_eq!(
+      path.with_extension("css").to_string(),
+      "/static/app.min.css"
+    );
+    assert_eq!(path.with_extension("").to_string(), "/static/app.min");
+    let path = Path::of_abempty_from_strs(&["report.pdf;v=2"]);
+    assert_eq!(path.extension(), Some("pdf".to_string()));
+    assert_eq!(path.with_extension("txt").to_string(), "/report.txt;v=2");
+    let path = Path::of_abempty_from_strs(&[".htaccess"]);
+    assert_eq!(path.extension(), None);
+    assert_eq!(path.with_extension("bak").to_string(), "/.htaccess.bak");
+    let path = Path::of_abempty_from_strs(&["dir", ""]);
+    assert_eq!(path.extension(), None);
+    assert_eq!(path.with_extension("txt"), path);
+  }
+
+  #[test]
+  fn test_join() {
+    let base = Path::of_abempty_from_strs(&["a", "b"]);
+    let relative = Path::of_rootless_from_strs(&["c", "d"]);
+    assert_eq!(base.join(&relative).to_string(), "/a/b/c/d");
+    let base = Path::of_abempty_from_strs(&["a", ""]);
+    assert_eq!(base.join(&relative).to_string(), "/a/c/d");
+    let absolute = Path::of_absolute_from_strs(&["x"]);
+    assert_eq!(base.join(&absolute).to_string(), "/x");
+    assert_eq!(base.join(&absolute).type_name(), "abempty_path");
+    assert_eq!(Path::of_empty().join(&relative).to_string(), "/c/d");
+  }
+
+  #[test]
+  fn test_strip_prefix() {
+    let path = Path::of_abempty_from_strs(&["static", "css", "app.css"]);
+    let prefix = Path::of_absolute_from_strs(&["static"]);
+    assert!(path.starts_with(&prefix));
+    assert_eq!(
+      path.strip_prefix(&prefix).unwrap().to_string(),
+      "css/app.css"
+    );
+    let prefix = Path::of_absolute_from_strs(&["static", ""]);
+    assert!(path.starts_with(&prefix));
+    let prefix = Path::of_absolute_from_strs(&["stat"]);
+    assert!(!path.starts_with(&prefix));
+    let prefix = Path::of_rootless_from_strs(&["static"]);
+    assert!(!path.starts_with(&prefix));
+    assert!(path.starts_with(&Path::of_absolute_from_strs(&[])));
+    assert!(path.starts_with(&Path::of_empty()));
+    assert_eq!(path.strip_prefix(&path).unwrap(), Path::of_empty());
+  }
+
+  #[test]
+  fn test_strip_prefix_keeps_relative_form() {
+    let prefix = Path::of_absolute_from_strs(&["a"]);
+    let rest = Path::of_absolute_from_strs(&["a", "b:c"])
+      .strip_prefix(&prefix)
+      .unwrap();
+    assert_eq!(rest.type_name(), "no_scheme_path");
+    assert_eq!(rest.to_string(), "./b:c");
+    let reparsed = RelativeRef::parse(&rest.to_string()).unwrap();
+    assert_eq!(reparsed.path().to_string(), "./b:c");
+
+    let rest = Path::of_absolute_from_strs(&["a", "", "b"])
+      .strip_prefix(&prefix)
+      .unwrap();
+    assert_eq!(rest.to_string(), ".//b");
+    assert!(!rest.is_rooted());
+    let reparsed = RelativeRef::parse(&rest.to_string()).unwrap();
+    assert!(reparsed.authority().is_none());
+    assert_eq!(reparsed.path().to_string(), ".//b");
+  }
 }
@@ -0,0 +1,161 @@
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// The port of an authority, `port = *DIGIT`.
+///
+/// `http://h:/` has an empty port, which is distinct from `http://h/` without one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Port {
+  Empty,
+  Number(u16),
+}
+
+impl std::fmt::Display for Port {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Port::Empty => Ok(()),
+      Port::Number(n) => write!(f, "{}", n),
+    }
+  }
+}
+
+impl From<u16> for Port {
+  fn from(src: u16) -> Self {
+    Port::Number(src)
+  }
+}
+
+impl FromStr for Port {
+  type Err = PortError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.is_empty() {
+      Ok(Port::Empty)
+    } else if !s.bytes().all(|b| b.is_ascii_digit()) {
+      Err(PortError::Invalid(s.to_string()))
+    } else {
+      u16::from_str(s)
+        .map(Port::Number)
+        .map_err(|_| PortError::OutOfRange(s.to_string()))
+    }
+  }
+}
+
+impl Port {
+  pub fn number(&self) -> Option<u16> {
+    match self {
+      Port::Empty => None,
+      Port::Number(n) => Some(*n),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    *self == Port::Empty
+  }
+}
+
+/// Error raised for a port that is malformed or rejected by a `PortPolicy`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortError {
+  Invalid(String),
+  OutOfRange(String),
+  Zero,
+  Empty,
+}
+
+impl std::fmt::Display for PortError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PortError::Invalid(s) => write!(f, "invalid port: {:?}", s),
+      PortError::OutOfRange(s) => write!(f, "port out of range 0-65535: {}", s),
+      PortError::Zero => write!(f, "port 0 is not allowed"),
+      PortError::Empty => write!(f, "empty port is not allowed"),
+    }
+  }
+}
+
+impl std::error::Error for PortError {}
+
+/// What to accept beyond RFC 3986, which allows any port up to 65535, including 0 and
+/// the empty port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortPolicy {
+  pub allow_zero: bool,
+  pub allow_empty: bool,
+  /// Drops an empty port, so that `http://h:/` becomes `http://h/`.
+  pub empty_as_absent: bool,
+}
+
+impl Default for PortPolicy {
+  fn default() -> Self {
+    PortPolicy {
+      allow_zero: true,
+      allow_empty: true,
+      empty_as_absent: false,
+    }
+  }
+}
+
+impl PortPolicy {
+  /// Rejects port 0 and drops empty ports.
+  pub fn strict() -> Self {
+    Self {
+      allow_zero: false,
+      allow_empty: true,
+      empty_as_absent: true,
+    }
+  }
+
+  /// Checks `port` and returns the port to keep.
+  pub fn apply(&self, port: Option<Port>) -> Result<Option<Port>, PortError> {
+    match port {
+      Some(Port::Number(0)) if !self.allow_zero => Err(PortError::Zero),
+      Some(Port::Empty) if !self.allow_empty => Err(PortError::Empty),
+      Some(Port::Empty) if self.empty_as_absent => Ok(None),
+      port => Ok(port),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Port, PortError, PortPolicy};
+
+  #[test]
+  fn test_from_str() {
+    assert_eq!("".parse::<Port>(), Ok(Port::Empty));
+    assert_eq!("8080".parse::<Port>(), Ok(Port::Number(8080)));
+    assert_eq!("65535".parse::<Port>(), Ok(Port::Number(65535)));
+    assert_eq!(
+      "65536".parse::<Port>(),
+      Err(PortError::OutOfRange("65536".to_string()))
+    );
+    assert_eq!(
+      "-1".parse::<Port>(),
+      Err(PortError::Invalid("-1".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_policy() {
+    let default = PortPolicy::default();
+    let strict = PortPolicy::strict();
+    assert_eq!(default.apply(Some(Port::Empty)), Ok(Some(Port::Empty)));
+    assert_eq!(
+      default.apply(Some(Port::Number(0))),
+      Ok(Some(Port::Number(0)))
+    );
+    assert_eq!(strict.apply(Some(Port::Empty)), Ok(None));
+    assert_eq!(strict.apply(Some(Port::Number(0))), Err(PortError::Zero));
+    assert_eq!(
+      strict.apply(Some(Port::Number(80))),
+      Ok(Some(Port::Number(80)))
+    );
+    assert_eq!(strict.apply(None), Ok(None));
+    let no_empty = PortPolicy {
+      allow_empty: false,
+      ..PortPolicy::default()
+    };
+    assert_eq!(no_empty.apply(Some(Port::Empty)), Err(PortError::Empty));
+  }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+
+use crate::ast::host_name::HostName;
+use crate::ast::uri::Uri;
+
+static EMBEDDED: Lazy<PublicSuffixList> =
+  Lazy::new(|| PublicSuffixList::parse(include_str!("../../data/public_suffix_list.dat")));
+
+/// Rules of the [Public Suffix List](https://publicsuffix.org/list/), covering both the
+/// ICANN and the private sections.
+///
+/// `PublicSuffixList::embedded` is the copy of the list compiled into this crate, which is
+/// subject to the Mozilla Public License, v. 2.0 (see `LICENSE-MPL-2.0`); use `from_file` to
+/// load a newer one. Rules are matched against ASCII host names, so
+/// internationalized names must be in their `xn--` form to match non-ASCII rules.
+///
+/// ```rust
+/// use uri_rs::{HostName, PublicSuffixList};
+/// let psl = PublicSuffixList::parse("com\n*.ck\n!www.ck\n");
+/// assert_eq!(psl.public_suffix(&HostName::from("www.example.com")), Some("com".to_string()));
+/// assert_eq!(psl.registrable_domain(&HostName::from("a.b.ck")), Some("a.b.ck".to_string()));
+/// assert_eq!(psl.registrable_domain(&HostName::from("www.ck")), Some("www.ck".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicSuffixList {
+  rules: HashSet<String>,
+  wildcards: HashSet<String>,
+  exceptions: HashSet<String>,
+}
+
+impl Default for PublicSuffixList {
+  fn default() -> Self {
+    PublicSuffixList {
+      rules: HashSet::default(),
+      wildcards: HashSet::default(),
+      exceptions: HashSet::default(),
+    }
+  }
+}
+
+impl PublicSuffixList {
+  /// Parses the text format of `public_suffix_list.dat`: one rule per line, `//` comments.
+  pub fn parse(text: &str) -> Self {
+    let mut list = Self::default();
+    for line in text.lines() {
+      let rule = match line.split_whitespace().next() {
+        Some(rule) if !rule.starts_with("//") => rule.to_lowercase(),
+        _ => continue,
+      };
+      if let Some(rule) = rule.strip_prefix('!') {
+        list.exceptions.insert(rule.to_string());
+      } else if let Some(rule) = rule.strip_prefix("*.") {
+        list.wildcards.insert(rule.to_string());
+      } else {
+        list.rules.insert(rule);
+      }
+    }
+    list
+  }
+
+  pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+    std::fs::read_to_string(path).map(|text| Self::parse(&text))
+  }
+
+  /// The list compiled into this crate.
+  pub fn embedded() -> &'static PublicSuffixList {
+    &EMBEDDED
+  }
+
+  /// The public suffix (eTLD) of `host`, or `None` for IP addresses and names that are not
+  /// a sequence of non-empty labels.
+  ///
+  /// A name matching no rule has its last label as public suffix, per the implicit `*` rule.
+  pub fn public_suffix(&self, host: &HostName) -> Option<String> {
+    let labels = Self::labels(host)?;
+    Some(labels[self.public_suffix_start(&labels)..].join("."))
+  }
+
+  /// The public suffix of `host` plus one label (eTLD+1), or `None` if `host` is itself a
+  /// public suffix.
+  pub fn registrable_domain(&self, host: &HostName) -> Option<String> {
+    let labels = Self::labels(host)?;
+    match self.public_suffix_start(&labels) {
+      0 => None,
+      start => Some(labels[start - 1..].join(".")),
+    }
+  }
+
+  fn labels(host: &HostName) -> Option<Vec<String>> {
+    if host.ip_address().is_some() || host.to_string().starts_with('[') {
+      return None;
+    }
+    let name = host.to_string().to_ascii_lowercase();
+    let name = name.strip_suffix('.').unwrap_or(&name);
+    let labels = name.split('.').map(|s| s.to_string()).collect::<Vec<_>>();
+    if labels.iter().any(|label| label.is_empty()) {
+      None
+    } else {
+      Some(labels)
+    }
+  }
+
+  // Index of the first label of the public suffix. Candidates are tried from the longest,
+  // so the first match is the prevailing rule; an exception is always longer than the
+  // wildcard it overrides.
+  fn public_suffix_start(&self, labels: &[String]) -> usize {
+    (0..labels.len())
+      .find_map(|i| {
+        let candidate = labels[i..].join(".");
+        let parent = labels[i + 1..].join(".");
+        if self.exceptions.contains(&candidate) {
+          Some(i + 1)
+        } else if self.rules.contains(&candidate)
+          || (i + 1 < labels.len() && self.wildcards.contains(&parent))
+        {
+          Some(i)
+        } else {
+          None
+        }
+      })
+      .unwrap_or(labels.len() - 1)
+  }
+}
+
+impl HostName {
+  /// The public suffix according to the embedded Public Suffix List.
+  pub fn public_suffix(&self) -> Option<String> {
+    PublicSuffixList::embedded().public_suffix(self)
+  }
+
+  /// The registrable domain (eTLD+1) according to the embedded Public Suffix List.
+  pub fn registrable_domain(&self) -> Option<String> {
+    PublicSuffixList::embedded().registrable_domain(self)
+  }
+}
+
+impl Uri {
+  /// Whether both URIs have the same scheme and the same site, using the embedded Public
+  /// Suffix List.
+  pub fn same_site(&self, other: &Uri) -> bool {
+    self.same_site_with(other, PublicSuffixList::embedded())
+  }
+
+  /// Whether both URIs have the same scheme and the same site, that is the registrable
+  /// domain of the host, or the host itself when it has none, such as an IP address.
+  pub fn same_site_with(&self, other: &Uri, list: &PublicSuffixList) -> bool {
+    let site = |uri: &Uri| {
+      uri.authority().map(|authority| {
+        let host_name = authority.host_name();
+        list
+          .registrable_domain(host_name)
+          .unwrap_or_else(|| host_name.to_string().to_ascii_lowercase())
+      })
+    };
+    self.schema() == other.schema()
+      && matches!((site(self), site(other)), (Some(a), Some(b)) if a == b)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{HostName, PublicSuffixList, Uri};
+
+  #[test]
+  fn test_embedded() {
+    let suffix = |s: &str| HostName::from(s).public_suffix();
+    let domain = |s: &str| HostName::from(s).registrable_domain();
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(suffix("www.example.com"), some("com"));
+    assert_eq!(domain("www.example.com"), some("example.com"));
+    assert_eq!(domain("WWW.Example.COM."), some("example.com"));
+    assert_eq!(suffix("a.b.example.co.uk"), some("co.uk"));
+    assert_eq!(domain("a.b.example.co.uk"), some("example.co.uk"));
+    assert_eq!(domain("co.uk"), None);
+    assert_eq!(domain("user.github.io"), some("user.github.io"));
+    assert_eq!(suffix("example.unknowntld"), some("unknowntld"));
+    assert_eq!(domain("example.unknowntld"), some("example.unknowntld"));
+    assert_eq!(domain("localhost"), None);
+    assert_eq!(suffix("192.168.0.1"), None);
+    assert_eq!(suffix("[::1]"), None);
+    assert_eq!(suffix("a..com"), None);
+    assert_eq!(suffix(""), None);
+  }
+
+  #[test]
+  fn test_wildcard_and_exception() {
+    let psl = PublicSuffixList::parse("// comment\ncom\n*.ck\n!www.ck\n\n*.kawasaki.jp\n");
+    let suffix = |s: &str| psl.public_suffix(&HostName::from(s));
+    let domain = |s: &str| psl.registrable_domain(&HostName::from(s));
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(suffix("b.c.ck"), some("c.ck"));
+    assert_eq!(domain("b.c.ck"), some("b.c.ck"));
+    assert_eq!(domain("c.ck"), None);
+    assert_eq!(suffix("ck"), some("ck"));
+    assert_eq!(suffix("www.ck"), some("ck"));
+    assert_eq!(domain("a.www.ck"), some("www.ck"));
+    assert_eq!(domain("x.city.kawasaki.jp"), some("x.city.kawasaki.jp"));
+  }
+
+  #[test]
+  fn test_same_site() {
+    let uri = |s: &str| Uri::parse(s).unwrap();
+    assert!(uri("https://a.example.com/").same_site(&uri("https://b.example.com:8443/x")));
+    assert!(uri("https://example.co.uk/").same_site(&uri("https://www.example.co.uk/")));
+    assert!(!uri("https://a.github.io/").same_site(&uri("https://b.github.io/")));
+    assert!(!uri("https://a.example.com/").same_site(&uri("http://a.example.com/")));
+    assert!(uri("http://127.0.0.1/").same_site(&uri("http://127.0.0.1:8080/")));
+    assert!(!uri("http://127.0.0.1/").same_site(&uri("http://127.0.0.2/")));
+    assert!(!uri("mailto:a@example.com").same_site(&uri("mailto:a@example.com")));
+  }
+}
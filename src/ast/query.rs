@@ -3,6 +3,20 @@ use itertools::Itertools;
 use std::fmt::Formatter;
 use std::cmp::Ordering;
 
+use crate::ast::user_info::REDACTED;
+
+/// Query parameter names that commonly carry credentials.
+pub const SENSITIVE_QUERY_KEYS: &[&str] = &[
+  "access_token",
+  "api_key",
+  "apikey",
+  "password",
+  "secret",
+  "signature",
+  "sig",
+  "token",
+];
+
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct Query {
   params: Vec<(String, Option<String>)>,
@@ -69,6 +83,18 @@ impl Query {
     result
   }
 
+  pub fn key_values(&self) -> &Vec<(String, Option<String>)> {
+    &self.params
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.params.is_empty()
+  }
+
+  pub fn non_empty(&self) -> bool {
+    !self.is_empty()
+  }
+
   pub fn add(&mut self, key: String, value: String) {
     self.params.push((key, Some(value)));
   }
@@ -83,6 +109,23 @@ impl Query {
     }
   }
 
+  /// A copy with the values of the parameters named in `keys` replaced by `***`.
+  /// Names are compared case-insensitively.
+  pub fn redacted(&self, keys: &[&str]) -> Query {
+    let params = self
+      .params
+      .iter()
+      .map(|(k, v)| {
+        if keys.iter().any(|key| key.eq_ignore_ascii_case(k)) {
+          (k.clone(), v.as_ref().map(|_| REDACTED.to_string()))
+        } else {
+          (k.clone(), v.clone())
+        }
+      })
+      .collect_vec();
+    Self { params }
+  }
+
   pub fn get_param(&self, key: String) -> Option<Vec<&String>> {
     self.params().get(&key).map(|v| v.clone())
   }
@@ -0,0 +1,88 @@
+use std::fmt::Formatter;
+
+use crate::ast::authority::Authority;
+use crate::ast::path::Path;
+use crate::ast::query::Query;
+use crate::ast::uri::Fragment;
+use crate::parser::parsers::{Elms, uri_parsers, UriParseError};
+
+/// A relative reference (`relative-ref` in RFC 3986), such as `../e?x#f` or `//host/path`.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct RelativeRef {
+  authority: Option<Authority>,
+  path: Path,
+  query: Option<Query>,
+  fragment: Option<Fragment>,
+}
+
+impl Default for RelativeRef {
+  fn default() -> Self {
+    RelativeRef {
+      authority: Option::default(),
+      path: Path::default(),
+      query: Option::default(),
+      fragment: Option::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for RelativeRef {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}{}{}{}",
+      self
+        .authority
+        .as_ref()
+        .map(|a| format!("//{}", a.to_string()))
+        .unwrap_or("".to_string()),
+      self.path.to_string(),
+      self
+        .query
+        .as_ref()
+        .map(|q| format!("?{}", q.to_string()))
+        .unwrap_or("".to_string()),
+      self
+        .fragment
+        .as_ref()
+        .map(|s| format!("#{}", s))
+        .unwrap_or("".to_string())
+    )
+  }
+}
+
+impl RelativeRef {
+  pub fn parse(text: &str) -> Result<RelativeRef, nom::Err<UriParseError>> {
+    uri_parsers::relative_ref(Elms::new(text.as_bytes())).map(|(_, v)| v)
+  }
+
+  pub fn new(
+    authority: Option<Authority>,
+    path: Path,
+    query: Option<Query>,
+    fragment: Option<Fragment>,
+  ) -> Self {
+    Self {
+      authority,
+      path,
+      query,
+      fragment,
+    }
+  }
+
+  pub fn authority(&self) -> Option<&Authority> {
+    self.authority.as_ref()
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn query(&self) -> Option<&Query> {
+    self.query.as_ref()
+  }
+
+  pub fn fragment(&self) -> Option<&Fragment> {
+    self.fragment.as_ref()
+  }
+}
@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+use percent_encoding::percent_decode_str;
+
+use crate::ast::path::Path;
+use crate::ast::uri::Uri;
+
+/// How a `Router` compares paths with its routes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePolicy {
+  /// Compares static text in ASCII case-insensitively; captured values keep their case.
+  pub case_insensitive: bool,
+  /// Treats `/users/` like `/users`, both in routes and in paths.
+  pub ignore_trailing_slash: bool,
+}
+
+impl Default for RoutePolicy {
+  fn default() -> Self {
+    RoutePolicy {
+      case_insensitive: false,
+      ignore_trailing_slash: false,
+    }
+  }
+}
+
+/// Error raised for a malformed route or a route that is already taken.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteError {
+  InvalidPattern(String),
+  Conflict(String),
+}
+
+impl std::fmt::Display for RouteError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RouteError::InvalidPattern(msg) => write!(f, "invalid route: {}", msg),
+      RouteError::Conflict(pattern) => write!(f, "route already exists: {}", pattern),
+    }
+  }
+}
+
+impl std::error::Error for RouteError {}
+
+/// The route that a path resolved to, with the captured parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteMatch<'a, T> {
+  value: &'a T,
+  pattern: &'a str,
+  params: Vec<(&'a str, String)>,
+}
+
+impl<'a, T> RouteMatch<'a, T> {
+  pub fn value(&self) -> &'a T {
+    self.value
+  }
+
+  /// The route as it was inserted.
+  pub fn pattern(&self) -> &'a str {
+    self.pattern
+  }
+
+  /// The captured parameters in route order. `:name` and `{name}` values are
+  /// percent-decoded, while a `*name` value is the rest of the path as written, still
+  /// percent-encoded, so that `a%2Fb/c` and `a/b/c` stay apart.
+  pub fn params(&self) -> &Vec<(&'a str, String)> {
+    &self.params
+  }
+
+  /// The value of the parameter `name`, encoded as described on `params`.
+  pub fn param(&self, name: &str) -> Option<&str> {
+    self
+      .params
+      .iter()
+      .find(|(n, _)| *n == name)
+      .map(|(_, v)| v.as_str())
+  }
+}
+
+/// A path router over a segment trie.
+///
+/// A route is an absolute path whose segments are one of:
+///
+/// - static text, such as `users`;
+/// - `:name`, capturing a whole non-empty segment;
+/// - a prefix and/or suffix around `{name}`, such as `v{major}` or `{file}.json`, capturing
+///   the non-empty rest of the segment;
+/// - `*name` as the last segment, capturing the remaining segments joined with `/`.
+///
+/// Static segments win over affixed parameters, which win over `:name`, which wins over
+/// `*name`; when a branch fails further down, the next one is tried. Paths are matched on
+/// percent-decoded segments, so `%2F` stays within a segment, and static text in a route is
+/// percent-decoded when inserted, so `/a%20b` and `/a b` are the same route. See
+/// `RouteMatch::params` for how captured values are encoded.
+///
+/// ```rust
+/// use uri_rs::{Router, Uri};
+/// let mut router = Router::new();
+/// router.insert("/users/:id", "user").unwrap();
+/// router.insert("/users/me", "me").unwrap();
+/// router.insert("/api/v{major}/*rest", "api").unwrap();
+/// let uri = Uri::parse("https://example.com/api/v2/a/b%20c").unwrap();
+/// let matched = router.route(&uri).unwrap();
+/// assert_eq!(*matched.value(), "api");
+/// assert_eq!(matched.param("major"), Some("2"));
+/// assert_eq!(matched.param("rest"), Some("a/b%20c"));
+/// assert_eq!(*router.route(&Uri::parse("https://example.com/users/me").unwrap()).unwrap().value(), "me");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Router<T> {
+  policy: RoutePolicy,
+  root: Node<T>,
+}
+
+impl<T> Default for Router<T> {
+  fn default() -> Self {
+    Router {
+      policy: RoutePolicy::default(),
+      root: Node::default(),
+    }
+  }
+}
+
+impl<T> Router<T> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_policy(policy: RoutePolicy) -> Self {
+    Self {
+      policy,
+      root: Node::default(),
+    }
+  }
+
+  pub fn policy(&self) -> &RoutePolicy {
+    &self.policy
+  }
+
+  pub fn insert(&mut self, pattern: &str, value: T) -> Result<(), RouteError> {
+    let invalid = |msg: &str| RouteError::InvalidPattern(format!("{}: {:?}", msg, pattern));
+    let segments = pattern
+      .strip_prefix('/')
+      .ok_or_else(|| invalid("a route starts with `/`"))?
+      .split('/')
+      .map(|s| s.to_string())
+      .collect_vec();
+    let segments = self.normalize(segments);
+    let case_insensitive = self.policy.case_insensitive;
+    let fold = |s: &str| {
+      if case_insensitive {
+        s.to_ascii_lowercase()
+      } else {
+        s.to_string()
+      }
+    };
+    let segments = segments
+      .iter()
+      .map(|segment| SegmentPattern::parse(segment).map_err(|msg| invalid(&msg)))
+      .collect::<Result<Vec<_>, _>>()?;
+    let mut names: Vec<String> = Vec::new();
+    for segment in segments.iter() {
+      if let SegmentPattern::Param(name)
+      | SegmentPattern::Affix { name, .. }
+      | SegmentPattern::CatchAll(name) = segment
+      {
+        if names.contains(name) {
+          return Err(invalid(&format!("duplicate parameter {:?}", name)));
+        }
+        names.push(name.clone());
+      }
+    }
+    let len = segments.len();
+    let mut node = &mut self.root;
+    for (idx, segment) in segments.into_iter().enumerate() {
+      match segment {
+        SegmentPattern::Static(s) => node = node.statics.entry(fold(&s)).or_default(),
+        SegmentPattern::Param(_) => node = node.param.get_or_insert_with(Box::default),
+        SegmentPattern::Affix { prefix, suffix, .. } => {
+          let (prefix, suffix) = (fold(&prefix), fold(&suffix));
+          let position = |affixes: &Vec<(String, String, Node<T>)>| {
+            affixes
+              .iter()
+              .position(|(p, s, _)| *p == prefix && *s == suffix)
+          };
+          if position(&node.affixes).is_none() {
+            node
+              .affixes
+              .push((prefix.clone(), suffix.clone(), Node::default()));
+            // Longer literals first, so that `v{n}.json` is tried before `v{n}`.
+            node
+              .affixes
+              .sort_by_key(|(p, s, _)| std::cmp::Reverse(p.len() + s.len()));
+          }
+          let pos = position(&node.affixes).unwrap();
+          node = &mut node.affixes[pos].2
+        }
+        SegmentPattern::CatchAll(_) => {
+          if idx + 1 != len {
+            return Err(invalid("`*` must be the last segment"));
+          }
+          if node.catch_all.is_some() {
+            return Err(RouteError::Conflict(pattern.to_string()));
+          }
+          node.catch_all = Some(Route::new(value, names, pattern));
+          return Ok(());
+        }
+      }
+    }
+    if node.route.is_some() {
+      return Err(RouteError::Conflict(pattern.to_string()));
+    }
+    node.route = Some(Route::new(value, names, pattern));
+    Ok(())
+  }
+
+  /// The route for the path of `uri`.
+  pub fn route(&self, uri: &Uri) -> Option<RouteMatch<'_, T>> {
+    self.at(uri.path())
+  }
+
+  /// The route for `path`, or `None` if no route matches. A segment that is not UTF-8 once
+  /// decoded matches neither static text nor `:name` or `{name}`, but a `*name` takes it.
+  pub fn at(&self, path: &Path) -> Option<RouteMatch<'_, T>> {
+    let segments = self.normalize(path.parts().clone());
+    let mut values = Vec::new();
+    let route = self.root.find(&segments, &self.policy, &mut values)?;
+    Some(RouteMatch {
+      value: &route.value,
+      pattern: &route.pattern,
+      params: route
+        .names
+        .iter()
+        .map(|name| name.as_str())
+        .zip(values)
+        .collect_vec(),
+    })
+  }
+
+  // `/` has no segments, and without a strict trailing slash neither has the last one.
+  fn normalize(&self, mut segments: Vec<String>) -> Vec<String> {
+    if segments.len() == 1 && segments[0].is_empty() {
+      segments.clear();
+    }
+    if self.policy.ignore_trailing_slash && segments.last().map(|s| s.is_empty()) == Some(true) {
+      segments.pop();
+    }
+    segments
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Route<T> {
+  value: T,
+  names: Vec<String>,
+  pattern: String,
+}
+
+impl<T> Route<T> {
+  fn new(value: T, names: Vec<String>, pattern: &str) -> Self {
+    Self {
+      value,
+      names,
+      pattern: pattern.to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+  route: Option<Route<T>>,
+  statics: HashMap<String, Node<T>>,
+  affixes: Vec<(String, String, Node<T>)>,
+  param: Option<Box<Node<T>>>,
+  catch_all: Option<Route<T>>,
+}
+
+impl<T> Default for Node<T> {
+  fn default() -> Self {
+    Node {
+      route: None,
+      statics: HashMap::new(),
+      affixes: Vec::new(),
+      param: None,
+      catch_all: None,
+    }
+  }
+}
+
+impl<T> Node<T> {
+  fn find(
+    &self,
+    segments: &[String],
+    policy: &RoutePolicy,
+    values: &mut Vec<String>,
+  ) -> Option<&Route<T>> {
+    let (first, rest) = match segments.split_first() {
+      Some(split) => split,
+      None => return self.route.as_ref(),
+    };
+    // A segment is decoded only to be compared or captured, so a catch-all also takes
+    // segments that are not UTF-8 once decoded.
+    if let Ok(segment) = percent_decode_str(first).decode_utf8() {
+      if let Some(route) = self.find_decoded(&segment, rest, policy, values) {
+        return Some(route);
+      }
+    }
+    let rest = segments.join("/");
+    match &self.catch_all {
+      Some(route) if !rest.is_empty() => {
+        values.push(rest);
+        Some(route)
+      }
+      _ => None,
+    }
+  }
+
+  fn find_decoded(
+    &self,
+    segment: &str,
+    rest: &[String],
+    policy: &RoutePolicy,
+    values: &mut Vec<String>,
+  ) -> Option<&Route<T>> {
+    let key = if policy.case_insensitive {
+      segment.to_ascii_lowercase()
+    } else {
+      segment.to_string()
+    };
+    if let Some(route) = self
+      .statics
+      .get(&key)
+      .and_then(|child| child.find(rest, policy, values))
+    {
+      return Some(route);
+    }
+    for (prefix, suffix, child) in self.affixes.iter() {
+      if key.len() > prefix.len() + suffix.len()
+        && key.starts_with(prefix.as_str())
+        && key.ends_with(suffix.as_str())
+      {
+        values.push(segment[prefix.len()..segment.len() - suffix.len()].to_string());
+        if let Some(route) = child.find(rest, policy, values) {
+          return Some(route);
+        }
+        values.pop();
+      }
+    }
+    if let Some(child) = self.param.as_ref().filter(|_| !segment.is_empty()) {
+      values.push(segment.to_string());
+      if let Some(route) = child.find(rest, policy, values) {
+        return Some(route);
+      }
+      values.pop();
+    }
+    None
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SegmentPattern {
+  Static(String),
+  Param(String),
+  Affix {
+    prefix: String,
+    name: String,
+    suffix: String,
+  },
+  CatchAll(String),
+}
+
+impl SegmentPattern {
+  // Static text is percent-decoded, as it is compared with decoded path segments.
+  fn parse(segment: &str) -> Result<SegmentPattern, String> {
+    let decode = |s: &str| {
+      percent_decode_str(s)
+        .decode_utf8()
+        .map(|s| s.to_string())
+        .map_err(|_| format!("{:?} is not UTF-8 once decoded", s))
+    };
+    let name = |s: &str| {
+      if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(s.to_string())
+      } else {
+        Err(format!("invalid parameter name {:?}", s))
+      }
+    };
+    if let Some(s) = segment.strip_prefix(':') {
+      return name(s).map(SegmentPattern::Param);
+    }
+    if let Some(s) = segment.strip_prefix('*') {
+      return name(s).map(SegmentPattern::CatchAll);
+    }
+    match (segment.find('{'), segment.find('}')) {
+      (None, None) => decode(segment).map(SegmentPattern::Static),
+      (Some(open), Some(close))
+        if open < close && !segment[close + 1..].contains(&['{', '}'][..]) =>
+      {
+        let (prefix, suffix) = (&segment[..open], &segment[close + 1..]);
+        let name = name(&segment[open + 1..close])?;
+        if prefix.is_empty() && suffix.is_empty() {
+          Ok(SegmentPattern::Param(name))
+        } else {
+          Ok(SegmentPattern::Affix {
+            prefix: decode(prefix)?,
+            name,
+            suffix: decode(suffix)?,
+          })
+        }
+      }
+      _ => Err(format!("unbalanced braces in {:?}", segment)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{RouteError, RoutePolicy, Router, Uri};
+
+  fn route<'a>(
+    router: &'a Router<&'static str>,
+    uri: &str,
+  ) -> Option<(&'a str, Vec<(String, String)>)> {
+    router.route(&Uri::parse(uri).unwrap()).map(|m| {
+      (
+        *m.value(),
+        m.params()
+          .iter()
+          .map(|(n, v)| (n.to_string(), v.clone()))
+          .collect(),
+      )
+    })
+  }
+
+  fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+      .iter()
+      .map(|(n, v)| (n.to_string(), v.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn test_priority_and_backtracking() {
+    let mut router = Router::new();
+    router.insert("/", "root").unwrap();
+    router.insert("/users/:id", "user").unwrap();
+    router.insert("/users/me", "me").unwrap();
+    router.insert("/users/me/posts", "my posts").unwrap();
+    router.insert("/users/:id/posts", "posts").unwrap();
+    router.insert("/files/{name}.json", "json").unwrap();
+    router.insert("/files/:name", "file").unwrap();
+    router.insert("/static/*rest", "static").unwrap();
+    router.insert("/v{major}/items", "items").unwrap();
+    assert_eq!(route(&router, "http://h"), Some(("root", vec![])));
+    assert_eq!(route(&router, "http://h/"), Some(("root", vec![])));
+    assert_eq!(route(&router, "http://h/users/me"), Some(("me", vec![])));
+    assert_eq!(
+      route(&router, "http://h/users/42"),
+      Some(("user", params(&[("id", "42")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/users/me/posts"),
+      Some(("my posts", vec![]))
+    );
+    assert_eq!(
+      route(&router, "http://h/users/7/posts"),
+      Some(("posts", params(&[("id", "7")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/files/a.b.json"),
+      Some(("json", params(&[("name", "a.b")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/files/.json"),
+      Some(("file", params(&[("name", ".json")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/static/css/a%20b.css"),
+      Some(("static", params(&[("rest", "css/a%20b.css")])))
+    );
+    assert_eq!(route(&router, "http://h/static"), None);
+    assert_eq!(route(&router, "http://h/static/"), None);
+    assert_eq!(
+      route(&router, "http://h/v2/items"),
+      Some(("items", params(&[("major", "2")])))
+    );
+    assert_eq!(route(&router, "http://h/users/"), None);
+    assert_eq!(route(&router, "http://h/users/42/other"), None);
+  }
+
+  #[test]
+  fn test_decoding() {
+    let mut router = Router::new();
+    router.insert("/a b/:id", "decoded").unwrap();
+    router.insert("/static/*rest", "static").unwrap();
+    assert_eq!(
+      route(&router, "http://h/static/a%2Fb/c"),
+      Some(("static", params(&[("rest", "a%2Fb/c")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/static/a/b/c"),
+      Some(("static", params(&[("rest", "a/b/c")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/st%61tic/a%20b"),
+      Some(("static", params(&[("rest", "a%20b")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/a%20b/x%2Fy"),
+      Some(("decoded", params(&[("id", "x/y")])))
+    );
+    assert_eq!(route(&router, "http://h/a%20b/%FF"), None);
+    assert_eq!(
+      route(&router, "http://h/static/%FF.bin"),
+      Some(("static", params(&[("rest", "%FF.bin")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/static/%FF/a%2Fb"),
+      Some(("static", params(&[("rest", "%FF/a%2Fb")])))
+    );
+  }
+
+  #[test]
+  fn test_encoded_static_text() {
+    let mut router = Router::new();
+    router.insert("/a%20b/v%31{n}.json", "encoded").unwrap();
+    assert_eq!(
+      route(&router, "http://h/a%20b/v12.json"),
+      Some(("encoded", params(&[("n", "2")])))
+    );
+    assert_eq!(
+      route(&router, "http://h/a%20b/v%312.json"),
+      Some(("encoded", params(&[("n", "2")])))
+    );
+    assert_eq!(
+      router.insert("/a b/v1{n}.json", "again"),
+      Err(RouteError::Conflict("/a b/v1{n}.json".to_string()))
+    );
+    assert!(matches!(
+      router.insert("/%FF", "invalid"),
+      Err(RouteError::InvalidPattern(_))
+    ));
+  }
+
+  #[test]
+  fn test_policy() {
+    let mut strict = Router::new();
+    strict.insert("/Users/:id", "user").unwrap();
+    strict.insert("/list/", "list").unwrap();
+    assert_eq!(route(&strict, "http://h/users/1"), None);
+    assert_eq!(route(&strict, "http://h/list"), None);
+    assert_eq!(route(&strict, "http://h/list/"), Some(("list", vec![])));
+
+    let mut relaxed = Router::with_policy(RoutePolicy {
+      case_insensitive: true,
+      ignore_trailing_slash: true,
+    });
+    relaxed.insert("/Users/:id", "user").unwrap();
+    relaxed.insert("/list/", "list").unwrap();
+    relaxed.insert("/API/V{major}", "api").unwrap();
+    assert_eq!(
+      route(&relaxed, "http://h/USERS/AbC/"),
+      Some(("user", params(&[("id", "AbC")])))
+    );
+    assert_eq!(route(&relaxed, "http://h/list"), Some(("list", vec![])));
+    assert_eq!(
+      route(&relaxed, "http://h/api/v3"),
+      Some(("api", params(&[("major", "3")])))
+    );
+    assert_eq!(
+      relaxed.insert("/list", "again"),
+      Err(RouteError::Conflict("/list".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_insert_errors() {
+    let mut router = Router::new();
+    router.insert("/a/:id", 1).unwrap();
+    assert_eq!(
+      router.insert("/a/:other", 2),
+      Err(RouteError::Conflict("/a/:other".to_string()))
+    );
+    router.insert("/b/*rest", 3).unwrap();
+    assert!(matches!(
+      router.insert("/b/*all", 4),
+      Err(RouteError::Conflict(_))
+    ));
+    for pattern in [
+      "a",
+      "/:",
+      "/*",
+      "/*rest/more",
+      "/{}",
+      "/{a",
+      "/a}",
+      "/{a}{b}",
+      "/:a-b",
+    ]
+    .iter()
+    {
+      assert!(
+        matches!(
+          router.insert(pattern, 0),
+          Err(RouteError::InvalidPattern(_))
+        ),
+        "{}",
+        pattern
+      );
+    }
+  }
+
+  #[test]
+  fn test_many_routes() {
+    let mut router = Router::new();
+    for i in 0..5000 {
+      router.insert(&format!("/r{}/:id", i), i).unwrap();
+    }
+    let m = router
+      .route(&Uri::parse("http://h/r4321/x").unwrap())
+      .unwrap();
+    assert_eq!(*m.value(), 4321);
+    assert_eq!(m.pattern(), "/r4321/:id");
+    assert_eq!(m.param("id"), Some("x"));
+  }
+}
@@ -1,10 +1,29 @@
 use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
 
 use crate::parser::parsers::Elms;
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+/// A URI scheme.
+///
+/// Schemes are case-insensitive, so `HTTP` and `http` are equal and hash alike.
+/// The original spelling is kept for display.
+#[derive(Debug, Clone)]
 pub struct Scheme(String);
 
+impl PartialEq for Scheme {
+  fn eq(&self, other: &Self) -> bool {
+    self.0.eq_ignore_ascii_case(&other.0)
+  }
+}
+
+impl Eq for Scheme {}
+
+impl Hash for Scheme {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.as_lowercase().hash(state)
+  }
+}
+
 impl Default for Scheme {
   fn default() -> Self {
     Scheme(String::default())
@@ -41,8 +60,209 @@ impl From<Elms<'_>> for Scheme {
   }
 }
 
+impl From<KnownScheme> for Scheme {
+  fn from(src: KnownScheme) -> Self {
+    Self(src.as_str().to_string())
+  }
+}
+
 impl Scheme {
   pub fn new(value: String) -> Self {
     Self(value)
   }
+
+  pub fn known(&self) -> KnownScheme {
+    KnownScheme::from(self.0.as_str())
+  }
+
+  /// Whether this is one of the WHATWG URL Standard's special schemes.
+  pub fn is_special(&self) -> bool {
+    self.known().is_special()
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  /// The port used when a URI of this scheme has none.
+  pub fn default_port(&self) -> Option<u16> {
+    self.known().default_port()
+  }
+
+  pub fn as_lowercase(&self) -> String {
+    self.0.to_ascii_lowercase()
+  }
+}
+
+/// Well-known schemes, with `Other` holding any other scheme in lower case.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KnownScheme {
+  Http,
+  Https,
+  Ws,
+  Wss,
+  Ftp,
+  Ftps,
+  Sftp,
+  File,
+  Mailto,
+  Data,
+  Urn,
+  Tel,
+  Sms,
+  Ssh,
+  Git,
+  Ldap,
+  Ldaps,
+  Telnet,
+  News,
+  Nntp,
+  Blob,
+  About,
+  Javascript,
+  Other(String),
+}
+
+impl std::fmt::Display for KnownScheme {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl From<&str> for KnownScheme {
+  fn from(src: &str) -> Self {
+    match src.to_ascii_lowercase().as_str() {
+      "http" => KnownScheme::Http,
+      "https" => KnownScheme::Https,
+      "ws" => KnownScheme::Ws,
+      "wss" => KnownScheme::Wss,
+      "ftp" => KnownScheme::Ftp,
+      "ftps" => KnownScheme::Ftps,
+      "sftp" => KnownScheme::Sftp,
+      "file" => KnownScheme::File,
+      "mailto" => KnownScheme::Mailto,
+      "data" => KnownScheme::Data,
+      "urn" => KnownScheme::Urn,
+      "tel" => KnownScheme::Tel,
+      "sms" => KnownScheme::Sms,
+      "ssh" => KnownScheme::Ssh,
+      "git" => KnownScheme::Git,
+      "ldap" => KnownScheme::Ldap,
+      "ldaps" => KnownScheme::Ldaps,
+      "telnet" => KnownScheme::Telnet,
+      "news" => KnownScheme::News,
+      "nntp" => KnownScheme::Nntp,
+      "blob" => KnownScheme::Blob,
+      "about" => KnownScheme::About,
+      "javascript" => KnownScheme::Javascript,
+      other => KnownScheme::Other(other.to_string()),
+    }
+  }
+}
+
+impl KnownScheme {
+  pub fn as_str(&self) -> &str {
+    match self {
+      KnownScheme::Http => "http",
+      KnownScheme::Https => "https",
+      KnownScheme::Ws => "ws",
+      KnownScheme::Wss => "wss",
+      KnownScheme::Ftp => "ftp",
+      KnownScheme::Ftps => "ftps",
+      KnownScheme::Sftp => "sftp",
+      KnownScheme::File => "file",
+      KnownScheme::Mailto => "mailto",
+      KnownScheme::Data => "data",
+      KnownScheme::Urn => "urn",
+      KnownScheme::Tel => "tel",
+      KnownScheme::Sms => "sms",
+      KnownScheme::Ssh => "ssh",
+      KnownScheme::Git => "git",
+      KnownScheme::Ldap => "ldap",
+      KnownScheme::Ldaps => "ldaps",
+      KnownScheme::Telnet => "telnet",
+      KnownScheme::News => "news",
+      KnownScheme::Nntp => "nntp",
+      KnownScheme::Blob => "blob",
+      KnownScheme::About => "about",
+      KnownScheme::Javascript => "javascript",
+      KnownScheme::Other(name) => name,
+    }
+  }
+
+  /// `ftp`, `file`, `http`, `https`, `ws` and `wss`.
+  pub fn is_special(&self) -> bool {
+    matches!(
+      self,
+      KnownScheme::Ftp
+        | KnownScheme::File
+        | KnownScheme::Http
+        | KnownScheme::Https
+        | KnownScheme::Ws
+        | KnownScheme::Wss
+    )
+  }
+
+  /// The registered default port, if the scheme has one.
+  pub fn default_port(&self) -> Option<u16> {
+    match self {
+      KnownScheme::Http | KnownScheme::Ws => Some(80),
+      KnownScheme::Https | KnownScheme::Wss => Some(443),
+      KnownScheme::Ftp => Some(21),
+      KnownScheme::Ftps => Some(990),
+      KnownScheme::Sftp | KnownScheme::Ssh => Some(22),
+      KnownScheme::Telnet => Some(23),
+      KnownScheme::News | KnownScheme::Nntp => Some(119),
+      KnownScheme::Ldap => Some(389),
+      KnownScheme::Ldaps => Some(636),
+      KnownScheme::Git => Some(9418),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::{KnownScheme, Scheme, Uri};
+
+  #[test]
+  fn test_case_insensitive() {
+    let upper = Scheme::from("HTTP");
+    let lower = Scheme::from("http");
+    assert_eq!(upper, lower);
+    assert_eq!(upper.to_string(), "HTTP");
+    assert_eq!(upper.as_lowercase(), "http");
+
+    let mut routes = HashMap::new();
+    routes.insert(lower, "web");
+    let uri = Uri::parse("HTTP://example.com/").unwrap();
+    assert_eq!(routes.get(uri.schema()), Some(&"web"));
+    assert_eq!(uri.to_string(), "HTTP://example.com/");
+  }
+
+  #[test]
+  fn test_known() {
+    assert_eq!(Scheme::from("HTTPS").known(), KnownScheme::Https);
+    assert_eq!(Scheme::from("mailto").known(), KnownScheme::Mailto);
+    assert_eq!(
+      Scheme::from("Chrome-Extension").known(),
+      KnownScheme::Other("chrome-extension".to_string())
+    );
+    assert_eq!(Scheme::from(KnownScheme::Wss).to_string(), "wss");
+    assert!(Scheme::from("WS").is_special());
+    assert!(Scheme::from("file").is_special());
+    assert!(!Scheme::from("mailto").is_special());
+    assert!(!Scheme::from("ssh").is_special());
+  }
+
+  #[test]
+  fn test_default_port() {
+    assert_eq!(Scheme::from("HTTP").default_port(), Some(80));
+    assert_eq!(Scheme::from("wss").default_port(), Some(443));
+    assert_eq!(Scheme::from("ssh").default_port(), Some(22));
+    assert_eq!(Scheme::from("file").default_port(), None);
+    assert_eq!(Scheme::from("x-custom").default_port(), None);
+  }
 }
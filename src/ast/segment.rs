@@ -0,0 +1,97 @@
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+
+/// A path segment with its `;`-separated parameters (matrix parameters).
+///
+/// `cars;color=red;year=2012` has the name `cars` and the parameters
+/// `[("color", Some("red")), ("year", Some("2012"))]`.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Segment {
+  name: String,
+  params: Vec<(String, Option<String>)>,
+}
+
+impl Default for Segment {
+  fn default() -> Self {
+    Segment {
+      name: String::default(),
+      params: Vec::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for Segment {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}{}",
+      self.name,
+      self
+        .params
+        .iter()
+        .map(|(k, v)| match v {
+          Some(v) => format!(";{}={}", k, v),
+          None => format!(";{}", k),
+        })
+        .join("")
+    )
+  }
+}
+
+impl From<&str> for Segment {
+  fn from(src: &str) -> Self {
+    let mut iter = src.split(';');
+    let name = iter.next().unwrap_or("").to_string();
+    let params = iter
+      .map(|kv| match kv.find('=') {
+        Some(idx) => (kv[..idx].to_string(), Some(kv[idx + 1..].to_string())),
+        None => (kv.to_string(), None),
+      })
+      .collect_vec();
+    Self { name, params }
+  }
+}
+
+impl From<&String> for Segment {
+  fn from(src: &String) -> Self {
+    Self::from(src.as_str())
+  }
+}
+
+impl Segment {
+  pub fn new(name: String, params: Vec<(String, Option<String>)>) -> Self {
+    Self { name, params }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn params(&self) -> &Vec<(String, Option<String>)> {
+    &self.params
+  }
+
+  pub fn has_params(&self) -> bool {
+    !self.params.is_empty()
+  }
+
+  pub fn get_param(&self, key: &str) -> Option<Vec<&String>> {
+    if self.params.iter().any(|(k, _)| k == key) {
+      Some(
+        self
+          .params
+          .iter()
+          .filter(|(k, _)| k == key)
+          .flat_map(|(_, v)| v.iter())
+          .collect_vec(),
+      )
+    } else {
+      None
+    }
+  }
+
+  pub fn add_param(&mut self, key: String, value: Option<String>) {
+    self.params.push((key, value));
+  }
+}
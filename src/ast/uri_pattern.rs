@@ -0,0 +1,613 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crate::ast::scheme::Scheme;
+use crate::ast::uri::Uri;
+
+/// A pattern over the components of a `Uri`, in the spirit of the WHATWG URLPattern API.
+///
+/// The pattern has the form `scheme://host[:port][path][?query][#fragment]`. Each component
+/// is made of:
+///
+/// - literal text, where `\` escapes the next character;
+/// - `:name`, a named group matching one or more characters other than `.` in the host and
+///   `/` in the path, and any characters elsewhere;
+/// - `*`, a wildcard matching any characters, including none, captured as `0`, `1`, ... in
+///   the order the wildcards appear;
+/// - `{...}`, a group, and the `?` modifier, which makes the preceding named group,
+///   wildcard or group optional;
+/// - `{?...}` after the path, an optional query, so `/users/:id{?*}` matches with or without
+///   a query.
+///
+/// A component left out of the pattern, such as the port, the path, the query or the
+/// fragment, matches anything. Scheme and host compare case-insensitively, and a URI with
+/// the default port of its scheme has the same empty port as one without a port.
+///
+/// ```rust
+/// use uri_rs::{Uri, UriPattern};
+/// let pattern = UriPattern::parse("https://*.example.com/api/v:version/users/:id{?*}").unwrap();
+/// let uri = Uri::parse("https://eu.example.com/api/v2/users/42?fields=name").unwrap();
+/// let captures = pattern.captures(&uri).unwrap();
+/// assert_eq!(captures["0"], "eu");
+/// assert_eq!(captures["version"], "2");
+/// assert_eq!(captures["id"], "42");
+/// assert_eq!(captures["1"], "fields=name");
+/// assert!(pattern.matches(&Uri::parse("https://eu.example.com/api/v2/users/42").unwrap()));
+/// assert!(!pattern.matches(&Uri::parse("https://example.com/api/v2/users/42").unwrap()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UriPattern {
+  source: String,
+  scheme: Option<ComponentPattern>,
+  host: Option<ComponentPattern>,
+  port: Option<ComponentPattern>,
+  path: Option<ComponentPattern>,
+  query: Option<ComponentPattern>,
+  fragment: Option<ComponentPattern>,
+}
+
+/// Error raised for a malformed `UriPattern`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UriPatternError {
+  InvalidSyntax(String),
+  DuplicateName(String),
+}
+
+impl std::fmt::Display for UriPatternError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      UriPatternError::InvalidSyntax(msg) => write!(f, "invalid URI pattern: {}", msg),
+      UriPatternError::DuplicateName(name) => write!(f, "duplicate group name: {}", name),
+    }
+  }
+}
+
+impl std::error::Error for UriPatternError {}
+
+impl PartialEq for UriPattern {
+  fn eq(&self, other: &Self) -> bool {
+    self.source == other.source
+  }
+}
+
+impl std::fmt::Display for UriPattern {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.source)
+  }
+}
+
+impl FromStr for UriPattern {
+  type Err = UriPatternError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    UriPattern::parse(s)
+  }
+}
+
+impl UriPattern {
+  pub fn parse(pattern: &str) -> Result<UriPattern, UriPatternError> {
+    let lexemes = lex(pattern)?;
+    let mut names = HashSet::new();
+    for lexeme in lexemes.iter() {
+      if let Lexeme::Name(name) = lexeme {
+        if !names.insert(name.clone()) {
+          return Err(UriPatternError::DuplicateName(name.clone()));
+        }
+      }
+    }
+    let parts = split_components(&lexemes)?;
+    let compile = |lexemes: Option<&[Lexeme]>, separator: Option<char>, lowercase: bool| {
+      lexemes
+        .map(|lexemes| ComponentPattern::compile(lexemes, separator, lowercase))
+        .transpose()
+    };
+    let scheme = compile(Some(parts.scheme), None, true)?;
+    let mut port = compile(parts.port, None, false)?;
+    // `https://h:443` means the same as `https://h`.
+    if let (Some(scheme), Some(port_pattern)) = (scheme.as_ref().and_then(|p| p.literal()), &port) {
+      if port_pattern.literal().and_then(|p| u16::from_str(&p).ok())
+        == Scheme::from(scheme.as_str()).default_port()
+      {
+        port = Some(ComponentPattern::compile(&[], None, false)?);
+      }
+    }
+    Ok(UriPattern {
+      source: pattern.to_string(),
+      scheme,
+      host: compile(Some(parts.host), Some('.'), true)?,
+      port,
+      path: compile(parts.path, Some('/'), false)?,
+      query: compile(parts.query, None, false)?,
+      fragment: compile(parts.fragment, None, false)?,
+    })
+  }
+
+  pub fn matches(&self, uri: &Uri) -> bool {
+    self.captures(uri).is_some()
+  }
+
+  /// The values of the named groups and wildcards if `uri` matches. Captures are the raw,
+  /// still percent-encoded text of the URI.
+  pub fn captures(&self, uri: &Uri) -> Option<HashMap<String, String>> {
+    let scheme = uri.schema();
+    let authority = uri.authority();
+    let host = authority
+      .map(|a| a.host_name().to_string().to_ascii_lowercase())
+      .unwrap_or_default();
+    let port = authority
+      .and_then(|a| a.port())
+      .filter(|port| Some(*port) != scheme.default_port())
+      .map(|port| port.to_string())
+      .unwrap_or_default();
+    let components = [
+      (&self.scheme, scheme.as_lowercase()),
+      (&self.host, host),
+      (&self.port, port),
+      (&self.path, uri.path().to_string()),
+      (
+        &self.query,
+        uri.query().map(|q| q.to_string()).unwrap_or_default(),
+      ),
+      (&self.fragment, uri.fragment().cloned().unwrap_or_default()),
+    ];
+    let mut captures = HashMap::new();
+    for (pattern, text) in components.iter() {
+      if let Some(pattern) = pattern {
+        captures.extend(pattern.captures(text)?);
+      }
+    }
+    Some(captures)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme {
+  Char(char),
+  Name(String),
+  Wildcard(usize),
+  Open,
+  Close,
+  Optional,
+}
+
+fn lex(pattern: &str) -> Result<Vec<Lexeme>, UriPatternError> {
+  let mut lexemes = Vec::new();
+  let mut wildcards = 0;
+  let mut query_group = false;
+  let mut chars = pattern.chars().peekable();
+  while let Some(c) = chars.next() {
+    let lexeme = match c {
+      '\\' => Lexeme::Char(chars.next().ok_or_else(|| {
+        UriPatternError::InvalidSyntax(format!("trailing escape: {:?}", pattern))
+      })?),
+      ':' if matches!(chars.peek(), Some(c) if c.is_ascii_alphabetic() || *c == '_') => {
+        let mut name = String::new();
+        while let Some(c) = chars.peek().copied().filter(|c| is_name_char(*c)) {
+          name.push(c);
+          chars.next();
+        }
+        Lexeme::Name(name)
+      }
+      '*' => {
+        wildcards += 1;
+        Lexeme::Wildcard(wildcards - 1)
+      }
+      // `{?...}` is an optional query: `?` starts the component and the group can be left out.
+      '{' if chars.peek() == Some(&'?') => {
+        chars.next();
+        query_group = true;
+        lexemes.push(Lexeme::Char('?'));
+        Lexeme::Open
+      }
+      '{' => Lexeme::Open,
+      '}' if query_group => {
+        query_group = false;
+        lexemes.push(Lexeme::Close);
+        Lexeme::Optional
+      }
+      '}' => Lexeme::Close,
+      '?'
+        if matches!(
+          lexemes.last(),
+          Some(Lexeme::Name(_)) | Some(Lexeme::Wildcard(_)) | Some(Lexeme::Close)
+        ) =>
+      {
+        Lexeme::Optional
+      }
+      c => Lexeme::Char(c),
+    };
+    lexemes.push(lexeme);
+  }
+  Ok(lexemes)
+}
+
+fn is_name_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || c == '_'
+}
+
+struct Components<'a> {
+  scheme: &'a [Lexeme],
+  host: &'a [Lexeme],
+  port: Option<&'a [Lexeme]>,
+  path: Option<&'a [Lexeme]>,
+  query: Option<&'a [Lexeme]>,
+  fragment: Option<&'a [Lexeme]>,
+}
+
+// Splits the lexemes at the top-level `://`, `:`, `/`, `?` and `#` delimiters.
+fn split_components(lexemes: &[Lexeme]) -> Result<Components<'_>, UriPatternError> {
+  let top_level = |pred: &dyn Fn(usize, &Lexeme) -> bool, from: usize, to: usize| {
+    let mut depth = 0;
+    let mut brackets = 0;
+    (from..to).find(|i| {
+      match &lexemes[*i] {
+        Lexeme::Open => depth += 1,
+        Lexeme::Close => depth -= 1,
+        Lexeme::Char('[') => brackets += 1,
+        Lexeme::Char(']') => brackets -= 1,
+        _ => (),
+      }
+      depth == 0 && brackets == 0 && pred(*i, &lexemes[*i])
+    })
+  };
+  let len = lexemes.len();
+  let scheme_end = top_level(
+    &|i, l| {
+      *l == Lexeme::Char(':')
+        && lexemes.get(i + 1) == Some(&Lexeme::Char('/'))
+        && lexemes.get(i + 2) == Some(&Lexeme::Char('/'))
+    },
+    0,
+    len,
+  )
+  .ok_or_else(|| UriPatternError::InvalidSyntax("a pattern needs `scheme://`".to_string()))?;
+  let authority_start = scheme_end + 3;
+  let fragment_start = top_level(&|_, l| *l == Lexeme::Char('#'), authority_start, len);
+  let query_end = fragment_start.unwrap_or(len);
+  let query_start = top_level(&|_, l| *l == Lexeme::Char('?'), authority_start, query_end);
+  let path_end = query_start.unwrap_or(query_end);
+  let path_start = top_level(&|_, l| *l == Lexeme::Char('/'), authority_start, path_end);
+  let authority_end = path_start.unwrap_or(path_end);
+  let port_start = top_level(
+    &|_, l| *l == Lexeme::Char(':'),
+    authority_start,
+    authority_end,
+  );
+  let host_end = port_start.unwrap_or(authority_end);
+  Ok(Components {
+    scheme: &lexemes[..scheme_end],
+    host: &lexemes[authority_start..host_end],
+    port: port_start.map(|i| &lexemes[i + 1..authority_end]),
+    path: path_start.map(|i| &lexemes[i..path_end]),
+    query: query_start.map(|i| &lexemes[i + 1..query_end]),
+    fragment: fragment_start.map(|i| &lexemes[i + 1..]),
+  })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+  Literal(String),
+  Name(String),
+  Wildcard(usize),
+}
+
+// A run of atoms that the `?` modifier may make optional.
+#[derive(Debug, Clone, PartialEq)]
+struct Piece {
+  atoms: Vec<Atom>,
+  optional: bool,
+}
+
+// A component pattern. Optional pieces are kept as they are and decided while matching,
+// trying the alternative that includes them first.
+#[derive(Debug, Clone, PartialEq)]
+struct ComponentPattern {
+  pieces: Vec<Piece>,
+  separator: Option<char>,
+}
+
+impl ComponentPattern {
+  fn compile(
+    lexemes: &[Lexeme],
+    separator: Option<char>,
+    lowercase: bool,
+  ) -> Result<ComponentPattern, UriPatternError> {
+    let invalid = |msg: &str| UriPatternError::InvalidSyntax(msg.to_string());
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut group: Option<Vec<Atom>> = None;
+    for lexeme in lexemes {
+      let atom = match lexeme {
+        Lexeme::Char(c) if lowercase => Atom::Literal(c.to_ascii_lowercase().to_string()),
+        Lexeme::Char(c) => Atom::Literal(c.to_string()),
+        Lexeme::Name(name) => Atom::Name(name.clone()),
+        Lexeme::Wildcard(index) => Atom::Wildcard(*index),
+        Lexeme::Open if group.is_none() => {
+          group = Some(Vec::new());
+          continue;
+        }
+        Lexeme::Open => return Err(invalid("groups cannot be nested")),
+        Lexeme::Close => {
+          let atoms = group.take().ok_or_else(|| invalid("unmatched `}`"))?;
+          pieces.push(Piece {
+            atoms,
+            optional: false,
+          });
+          continue;
+        }
+        Lexeme::Optional if group.is_some() => return Err(invalid("`?` inside a group")),
+        Lexeme::Optional => {
+          let last = pieces.last_mut().ok_or_else(|| invalid("misplaced `?`"))?;
+          last.optional = true;
+          continue;
+        }
+      };
+      match group.as_mut() {
+        Some(atoms) => atoms.push(atom),
+        None => pieces.push(Piece {
+          atoms: vec![atom],
+          optional: false,
+        }),
+      }
+    }
+    if group.is_some() {
+      return Err(invalid("unmatched `{`"));
+    }
+    Ok(ComponentPattern { pieces, separator })
+  }
+
+  // The text of a pattern made of literals only.
+  fn literal(&self) -> Option<String> {
+    self
+      .pieces
+      .iter()
+      .map(|piece| {
+        if piece.optional {
+          None
+        } else {
+          Some(&piece.atoms)
+        }
+      })
+      .collect::<Option<Vec<_>>>()?
+      .into_iter()
+      .flatten()
+      .map(|atom| match atom {
+        Atom::Literal(s) => Some(s.as_str()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn captures(&self, text: &str) -> Option<HashMap<String, String>> {
+    let mut captures = Vec::new();
+    if self.match_pieces(0, text, &mut captures) {
+      Some(captures.into_iter().collect())
+    } else {
+      None
+    }
+  }
+
+  fn match_pieces(&self, piece: usize, text: &str, captures: &mut Vec<(String, String)>) -> bool {
+    match self.pieces.get(piece) {
+      None => text.is_empty(),
+      Some(p) => {
+        self.match_atoms(piece, 0, text, captures)
+          || (p.optional && self.match_pieces(piece + 1, text, captures))
+      }
+    }
+  }
+
+  fn match_atoms(
+    &self,
+    piece: usize,
+    index: usize,
+    text: &str,
+    captures: &mut Vec<(String, String)>,
+  ) -> bool {
+    let atom = match self.pieces[piece].atoms.get(index) {
+      Some(atom) => atom,
+      None => return self.match_pieces(piece + 1, text, captures),
+    };
+    let (name, min_len, stop) = match atom {
+      Atom::Literal(s) => {
+        return match text.strip_prefix(s.as_str()) {
+          Some(text) => self.match_atoms(piece, index + 1, text, captures),
+          None => false,
+        }
+      }
+      Atom::Name(name) => (name.clone(), 1, self.separator),
+      Atom::Wildcard(index) => (index.to_string(), 0, None),
+    };
+    // The longest run without the separator comes first, so groups are greedy.
+    let max_len = stop.and_then(|c| text.find(c)).unwrap_or(text.len());
+    let ends = (min_len..=max_len)
+      .rev()
+      .filter(|end| text.is_char_boundary(*end));
+    for end in ends {
+      captures.push((name.clone(), text[..end].to_string()));
+      if self.match_atoms(piece, index + 1, &text[end..], captures) {
+        return true;
+      }
+      captures.pop();
+    }
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+  use std::time::{Duration, Instant};
+
+  use crate::{Uri, UriPattern, UriPatternError};
+
+  fn captures(pattern: &str, uri: &str) -> Option<HashMap<String, String>> {
+    UriPattern::parse(pattern)
+      .unwrap()
+      .captures(&Uri::parse(uri).unwrap())
+  }
+
+  fn owned(pairs: &[(&str, &str)]) -> Option<HashMap<String, String>> {
+    Some(
+      pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn test_path() {
+    let pattern = "https://example.com/users/:id";
+    assert_eq!(
+      captures(pattern, "https://example.com/users/42"),
+      owned(&[("id", "42")])
+    );
+    assert_eq!(captures(pattern, "https://example.com/users/"), None);
+    assert_eq!(captures(pattern, "https://example.com/users/4/2"), None);
+    assert_eq!(
+      captures(
+        "https://example.com/files/*",
+        "https://example.com/files/a/b.txt"
+      ),
+      owned(&[("0", "a/b.txt")])
+    );
+    assert_eq!(
+      captures("http://h/:name.:ext", "http://h/archive.tar.gz"),
+      owned(&[("name", "archive.tar"), ("ext", "gz")])
+    );
+  }
+
+  #[test]
+  fn test_optional() {
+    let pattern = "https://example.com/posts{/:page}?";
+    assert_eq!(
+      captures(pattern, "https://example.com/posts/2"),
+      owned(&[("page", "2")])
+    );
+    assert_eq!(captures(pattern, "https://example.com/posts"), owned(&[]));
+    assert_eq!(
+      captures(
+        "https://example.com/:lang?/about",
+        "https://example.com//about"
+      ),
+      owned(&[])
+    );
+    assert_eq!(
+      captures("https://example.com/a\\?b", "https://example.com/a%3Fb"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_optional_query() {
+    let pattern = "https://*.example.com/api/v:version/users/:id{?*}";
+    let expected =
+      |query: &str| owned(&[("0", "eu"), ("version", "2"), ("id", "42"), ("1", query)]);
+    assert_eq!(
+      captures(pattern, "https://eu.example.com/api/v2/users/42"),
+      expected("")
+    );
+    assert_eq!(
+      captures(pattern, "https://eu.example.com/api/v2/users/42?"),
+      expected("")
+    );
+    assert_eq!(
+      captures(pattern, "https://eu.example.com/api/v2/users/42?x=1"),
+      expected("x=1")
+    );
+    assert_eq!(
+      captures(pattern, "https://eu.example.com/api/v2/users/42/x?x=1"),
+      None
+    );
+    let pattern = "https://example.com/search{?q=:term}";
+    assert_eq!(
+      captures(pattern, "https://example.com/search?q=rust"),
+      owned(&[("term", "rust")])
+    );
+    assert_eq!(captures(pattern, "https://example.com/search"), owned(&[]));
+    assert_eq!(captures(pattern, "https://example.com/search?p=1"), None);
+  }
+
+  #[test]
+  fn test_many_optional_groups() {
+    let path = format!("/{}", "{x/}?".repeat(40));
+    let started = Instant::now();
+    let pattern = UriPattern::parse(&format!("https://example.com{}", path)).unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let compiled = pattern.path.as_ref().unwrap();
+    assert_eq!(compiled.pieces.len(), 41);
+    assert_eq!(
+      compiled
+        .pieces
+        .iter()
+        .filter(|piece| piece.optional)
+        .count(),
+      40
+    );
+    assert!(pattern.matches(&Uri::parse("https://example.com/x/x/x/").unwrap()));
+    assert!(pattern.matches(&Uri::parse("https://example.com/").unwrap()));
+    assert!(!pattern.matches(&Uri::parse("https://example.com/y/").unwrap()));
+  }
+
+  #[test]
+  fn test_authority() {
+    let pattern = "https://:tenant.example.com:*/";
+    assert_eq!(
+      captures(pattern, "HTTPS://Acme.Example.com:8443/"),
+      owned(&[("tenant", "acme"), ("0", "8443")])
+    );
+    assert_eq!(
+      captures(pattern, "https://acme.example.com/"),
+      owned(&[("tenant", "acme"), ("0", "")])
+    );
+    assert_eq!(captures(pattern, "https://a.b.example.com/"), None);
+    let default_port = "https://example.com:443/";
+    assert!(captures(default_port, "https://example.com/").is_some());
+    assert!(captures(default_port, "https://example.com:443/").is_some());
+    assert!(captures(default_port, "https://example.com:8443/").is_none());
+    assert!(captures("http://[::1]:8080/", "http://[::1]:8080/").is_some());
+    assert!(captures("*://example.com", "wss://example.com/any?q#f").is_some());
+  }
+
+  #[test]
+  fn test_query_and_fragment() {
+    let pattern = "https://example.com/search?q=:term#:section";
+    assert_eq!(
+      captures(pattern, "https://example.com/search?q=rust#top"),
+      owned(&[("term", "rust"), ("section", "top")])
+    );
+    assert_eq!(captures(pattern, "https://example.com/search?q=rust"), None);
+    assert_eq!(
+      captures(
+        "https://example.com/search",
+        "https://example.com/search?q=rust#top"
+      ),
+      owned(&[])
+    );
+  }
+
+  #[test]
+  fn test_invalid() {
+    assert_eq!(
+      UriPattern::parse("https://example.com/:id/:id"),
+      Err(UriPatternError::DuplicateName("id".to_string()))
+    );
+    for s in [
+      "example.com/:id",
+      "https://example.com/{a",
+      "https://example.com/a}",
+      "https://example.com/{{a}}",
+      "https://example.com/{:a?}",
+      "https://example.com/\\",
+    ]
+    .iter()
+    {
+      assert!(
+        matches!(UriPattern::parse(s), Err(UriPatternError::InvalidSyntax(_))),
+        "{}",
+        s
+      );
+    }
+  }
+}
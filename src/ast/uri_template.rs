@@ -0,0 +1,868 @@
+use std::collections::HashMap;
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+use percent_encoding::percent_decode_str;
+
+use crate::ast::uri::Uri;
+use crate::parser::parsers::{uri_template_parsers, Elms};
+
+/// The value of a URI Template variable (RFC 6570, section 2.3).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+  String(String),
+  List(Vec<String>),
+  AssocArray(Vec<(String, String)>),
+}
+
+impl From<&str> for TemplateValue {
+  fn from(src: &str) -> Self {
+    TemplateValue::String(src.to_string())
+  }
+}
+
+impl From<String> for TemplateValue {
+  fn from(src: String) -> Self {
+    TemplateValue::String(src)
+  }
+}
+
+impl From<Vec<&str>> for TemplateValue {
+  fn from(src: Vec<&str>) -> Self {
+    TemplateValue::List(src.into_iter().map(|s| s.to_string()).collect_vec())
+  }
+}
+
+impl From<Vec<String>> for TemplateValue {
+  fn from(src: Vec<String>) -> Self {
+    TemplateValue::List(src)
+  }
+}
+
+impl From<Vec<(&str, &str)>> for TemplateValue {
+  fn from(src: Vec<(&str, &str)>) -> Self {
+    TemplateValue::AssocArray(
+      src
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect_vec(),
+    )
+  }
+}
+
+impl From<Vec<(String, String)>> for TemplateValue {
+  fn from(src: Vec<(String, String)>) -> Self {
+    TemplateValue::AssocArray(src)
+  }
+}
+
+impl TemplateValue {
+  /// Whether the value counts as undefined: an empty list or associative array.
+  pub fn is_undefined(&self) -> bool {
+    match self {
+      TemplateValue::String(_) => false,
+      TemplateValue::List(values) => values.is_empty(),
+      TemplateValue::AssocArray(pairs) => pairs.is_empty(),
+    }
+  }
+}
+
+/// The operator of an expression, such as `+` in `{+path}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+  Simple,
+  Reserved,
+  Fragment,
+  Label,
+  PathSegment,
+  PathParameter,
+  Query,
+  QueryContinuation,
+}
+
+impl std::fmt::Display for Operator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let s = match self {
+      Operator::Simple => "",
+      Operator::Reserved => "+",
+      Operator::Fragment => "#",
+      Operator::Label => ".",
+      Operator::PathSegment => "/",
+      Operator::PathParameter => ";",
+      Operator::Query => "?",
+      Operator::QueryContinuation => "&",
+    };
+    write!(f, "{}", s)
+  }
+}
+
+impl Operator {
+  pub fn from_char(c: char) -> Option<Operator> {
+    match c {
+      '+' => Some(Operator::Reserved),
+      '#' => Some(Operator::Fragment),
+      '.' => Some(Operator::Label),
+      '/' => Some(Operator::PathSegment),
+      ';' => Some(Operator::PathParameter),
+      '?' => Some(Operator::Query),
+      '&' => Some(Operator::QueryContinuation),
+      _ => None,
+    }
+  }
+
+  /// The text put before the first defined variable.
+  pub fn first(&self) -> &'static str {
+    match self {
+      Operator::Simple | Operator::Reserved => "",
+      Operator::Fragment => "#",
+      Operator::Label => ".",
+      Operator::PathSegment => "/",
+      Operator::PathParameter => ";",
+      Operator::Query => "?",
+      Operator::QueryContinuation => "&",
+    }
+  }
+
+  /// The text put between variables and exploded items.
+  pub fn separator(&self) -> &'static str {
+    match self {
+      Operator::Simple | Operator::Reserved | Operator::Fragment => ",",
+      Operator::Label => ".",
+      Operator::PathSegment => "/",
+      Operator::PathParameter => ";",
+      Operator::Query | Operator::QueryContinuation => "&",
+    }
+  }
+
+  /// Whether values are expanded as `name=value` pairs.
+  pub fn is_named(&self) -> bool {
+    matches!(
+      self,
+      Operator::PathParameter | Operator::Query | Operator::QueryContinuation
+    )
+  }
+
+  /// The text put after the name of an empty named value.
+  pub fn if_empty(&self) -> &'static str {
+    match self {
+      Operator::Query | Operator::QueryContinuation => "=",
+      _ => "",
+    }
+  }
+
+  /// Whether reserved characters and percent-encoded triplets are kept as they are.
+  pub fn allows_reserved(&self) -> bool {
+    matches!(self, Operator::Reserved | Operator::Fragment)
+  }
+}
+
+/// The modifier of a variable, `:max-length` or `*`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modifier {
+  Prefix(usize),
+  Explode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarSpec {
+  name: String,
+  modifier: Option<Modifier>,
+}
+
+impl std::fmt::Display for VarSpec {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self.modifier {
+      Some(Modifier::Prefix(len)) => write!(f, "{}:{}", self.name, len),
+      Some(Modifier::Explode) => write!(f, "{}*", self.name),
+      None => write!(f, "{}", self.name),
+    }
+  }
+}
+
+impl VarSpec {
+  pub fn new(name: String, modifier: Option<Modifier>) -> Self {
+    Self { name, modifier }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn modifier(&self) -> Option<Modifier> {
+    self.modifier
+  }
+}
+
+/// An expression, such as `{?q,lang}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+  operator: Operator,
+  var_specs: Vec<VarSpec>,
+}
+
+impl std::fmt::Display for Expression {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{{{}{}}}",
+      self.operator,
+      self.var_specs.iter().join(",")
+    )
+  }
+}
+
+impl Expression {
+  pub fn new(operator: Operator, var_specs: Vec<VarSpec>) -> Self {
+    Self {
+      operator,
+      var_specs,
+    }
+  }
+
+  pub fn operator(&self) -> Operator {
+    self.operator
+  }
+
+  pub fn var_specs(&self) -> &Vec<VarSpec> {
+    &self.var_specs
+  }
+
+  // RFC 6570, appendix A.
+  fn expand(&self, variables: &HashMap<String, TemplateValue>) -> String {
+    let op = self.operator;
+    let encode_value = |s: &str| encode(s, op.allows_reserved());
+    let mut result = String::new();
+    let defined = self.var_specs.iter().filter_map(|var_spec| {
+      variables
+        .get(&var_spec.name)
+        .filter(|value| !value.is_undefined())
+        .map(|value| (var_spec, value))
+    });
+    for (idx, (var_spec, value)) in defined.enumerate() {
+      result.push_str(if idx == 0 { op.first() } else { op.separator() });
+      let name = &var_spec.name;
+      let named = |value: &str, result: &mut String| {
+        result.push_str(name);
+        result.push_str(if value.is_empty() { op.if_empty() } else { "=" });
+        result.push_str(value);
+      };
+      // `name=value` for named operators, the bare value otherwise.
+      let push_value = |value: &str, result: &mut String| {
+        if op.is_named() {
+          named(value, result)
+        } else {
+          result.push_str(value)
+        }
+      };
+      match (value, var_spec.modifier) {
+        (TemplateValue::String(s), modifier) => {
+          let s = match modifier {
+            Some(Modifier::Prefix(len)) => encode_value(&s.chars().take(len).collect::<String>()),
+            _ => encode_value(s),
+          };
+          push_value(&s, &mut result)
+        }
+        (TemplateValue::List(values), Some(Modifier::Explode)) => {
+          let mut items = values.iter().map(|v| {
+            let mut item = String::new();
+            push_value(&encode_value(v), &mut item);
+            item
+          });
+          result.push_str(&items.join(op.separator()))
+        }
+        (TemplateValue::AssocArray(pairs), Some(Modifier::Explode)) => {
+          let mut items = pairs.iter().map(|(k, v)| {
+            let v = encode_value(v);
+            if op.is_named() && v.is_empty() {
+              format!("{}{}", encode_value(k), op.if_empty())
+            } else {
+              format!("{}={}", encode_value(k), v)
+            }
+          });
+          result.push_str(&items.join(op.separator()))
+        }
+        (TemplateValue::List(values), _) => {
+          let items = values.iter().map(|v| encode_value(v)).join(",");
+          push_value(&items, &mut result)
+        }
+        (TemplateValue::AssocArray(pairs), _) => {
+          let items = pairs
+            .iter()
+            .map(|(k, v)| format!("{},{}", encode_value(k), encode_value(v)))
+            .join(",");
+          push_value(&items, &mut result)
+        }
+      }
+    }
+    result
+  }
+
+  // Whether `c` can occur in an expansion of this expression.
+  fn can_contain(&self, c: char) -> bool {
+    self.operator.allows_reserved()
+      || c.is_ascii_alphanumeric()
+      || "-._~%,=".contains(c)
+      || self.operator.first().contains(c)
+      || self.operator.separator().contains(c)
+  }
+
+  // The variables of which `text` is the expansion, if it is one without ambiguity.
+  fn match_text(&self, text: &str) -> Option<Vec<(String, TemplateValue)>> {
+    if text.is_empty() {
+      return Some(Vec::new());
+    }
+    let op = self.operator;
+    let body = text.strip_prefix(op.first())?;
+    let items = body.split(op.separator()).collect_vec();
+    let exploded = self
+      .var_specs
+      .iter()
+      .filter(|v| v.modifier == Some(Modifier::Explode))
+      .collect_vec();
+    if exploded.len() > 1 {
+      return None;
+    }
+    let mut result: Vec<(String, TemplateValue)> = Vec::new();
+    if op.is_named() {
+      let mut exploded_value: Option<TemplateValue> = None;
+      for item in items {
+        let (key, value) = match item.find('=') {
+          Some(idx) => (&item[..idx], &item[idx + 1..]),
+          None => (item, ""),
+        };
+        let var_spec = self.var_specs.iter().find(|v| v.name == key);
+        match (var_spec, exploded.first()) {
+          (Some(v), _) if v.modifier != Some(Modifier::Explode) => {
+            if result.iter().any(|(name, _)| *name == v.name) {
+              return None;
+            }
+            result.push((v.name.clone(), decode_value(value, op)?));
+          }
+          (_, Some(v)) => {
+            let value = decode(value)?;
+            exploded_value = match (exploded_value, key == v.name) {
+              (None, true) => Some(TemplateValue::List(vec![value])),
+              (Some(TemplateValue::List(mut values)), true) => {
+                values.push(value);
+                Some(TemplateValue::List(values))
+              }
+              (None, false) => Some(TemplateValue::AssocArray(vec![(decode(key)?, value)])),
+              (Some(TemplateValue::AssocArray(mut pairs)), false) => {
+                pairs.push((decode(key)?, value));
+                Some(TemplateValue::AssocArray(pairs))
+              }
+              _ => return None,
+            };
+          }
+          _ => return None,
+        }
+      }
+      if let (Some(v), Some(value)) = (exploded.first(), exploded_value) {
+        result.push((v.name.clone(), value));
+      }
+    } else if self.var_specs.len() == 1 && exploded.is_empty() && op.separator() == "," {
+      result.push((self.var_specs[0].name.clone(), decode_value(body, op)?));
+    } else {
+      let last = self.var_specs.len() - 1;
+      if exploded.iter().any(|v| v.name != self.var_specs[last].name)
+        || (items.len() > self.var_specs.len() && exploded.is_empty())
+      {
+        return None;
+      }
+      for (idx, v) in self.var_specs.iter().enumerate() {
+        let value = match items.get(idx..) {
+          Some(rest) if idx == last && !exploded.is_empty() && !rest.is_empty() => {
+            if !op.allows_reserved() && rest.iter().all(|item| item.contains('=')) {
+              let pairs = rest.iter().map(|item| {
+                let idx = item.find('=').unwrap();
+                Some((decode(&item[..idx])?, decode(&item[idx + 1..])?))
+              });
+              TemplateValue::AssocArray(pairs.collect::<Option<Vec<_>>>()?)
+            } else {
+              TemplateValue::List(
+                rest
+                  .iter()
+                  .map(|item| decode(item))
+                  .collect::<Option<Vec<_>>>()?,
+              )
+            }
+          }
+          _ => match items.get(idx) {
+            Some(item) => decode_value(item, op)?,
+            None => break,
+          },
+        };
+        result.push((v.name.clone(), value));
+      }
+    }
+    Some(result)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+  Literal(String),
+  Expression(Expression),
+}
+
+impl std::fmt::Display for TemplatePart {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TemplatePart::Literal(s) => write!(f, "{}", s),
+      TemplatePart::Expression(e) => write!(f, "{}", e),
+    }
+  }
+}
+
+/// Error raised for a malformed template or an expansion that is not a URI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UriTemplateError {
+  InvalidTemplate(String),
+  InvalidUri(String),
+}
+
+impl std::fmt::Display for UriTemplateError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      UriTemplateError::InvalidTemplate(msg) => write!(f, "invalid URI template: {}", msg),
+      UriTemplateError::InvalidUri(msg) => write!(f, "expansion is not a URI: {}", msg),
+    }
+  }
+}
+
+impl std::error::Error for UriTemplateError {}
+
+/// A URI Template (RFC 6570) up to level 4: all operators, prefix modifiers and explode.
+///
+/// A prefix modifier on a list or an associative array is ignored.
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use uri_rs::{TemplateValue, UriTemplate};
+/// let template =
+///   UriTemplate::parse("https://api.x.com/repos{/owner,repo}/issues{?state,labels*}").unwrap();
+/// let mut variables = HashMap::new();
+/// variables.insert("owner".to_string(), TemplateValue::from("j5ik2o"));
+/// variables.insert("repo".to_string(), TemplateValue::from("uri-rs"));
+/// variables.insert("labels".to_string(), TemplateValue::from(vec!["bug", "help wanted"]));
+/// assert_eq!(
+///   template.expand(&variables).unwrap().to_string(),
+///   "https://api.x.com/repos/j5ik2o/uri-rs/issues?labels=bug&labels=help%20wanted"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct UriTemplate {
+  parts: Vec<TemplatePart>,
+}
+
+impl Default for UriTemplate {
+  fn default() -> Self {
+    UriTemplate {
+      parts: Vec::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for UriTemplate {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.parts.iter().join(""))
+  }
+}
+
+impl UriTemplate {
+  pub fn new(parts: Vec<TemplatePart>) -> Self {
+    Self { parts }
+  }
+
+  pub fn parse(text: &str) -> Result<UriTemplate, UriTemplateError> {
+    uri_template_parsers::uri_template(Elms::new(text.as_bytes()))
+      .map(|(_, v)| v)
+      .map_err(|e| UriTemplateError::InvalidTemplate(format!("{:?}", e)))
+  }
+
+  pub fn parts(&self) -> &Vec<TemplatePart> {
+    &self.parts
+  }
+
+  /// The names of the variables, in order of appearance.
+  pub fn variable_names(&self) -> Vec<&str> {
+    self
+      .parts
+      .iter()
+      .flat_map(|part| match part {
+        TemplatePart::Expression(e) => e.var_specs.iter().map(|v| v.name()).collect_vec(),
+        TemplatePart::Literal(_) => Vec::new(),
+      })
+      .unique()
+      .collect_vec()
+  }
+
+  /// Expands the template into a string, which need not be a URI.
+  pub fn expand_to_string(&self, variables: &HashMap<String, TemplateValue>) -> String {
+    self
+      .parts
+      .iter()
+      .map(|part| match part {
+        TemplatePart::Literal(s) => encode(s, true),
+        TemplatePart::Expression(e) => e.expand(variables),
+      })
+      .join("")
+  }
+
+  /// Expands the template and parses the result as a `Uri`.
+  pub fn expand(
+    &self,
+    variables: &HashMap<String, TemplateValue>,
+  ) -> Result<Uri, UriTemplateError> {
+    let expanded = self.expand_to_string(variables);
+    Uri::parse(&expanded)
+      .map_err(|e| UriTemplateError::InvalidUri(format!("{}: {:?}", expanded, e)))
+  }
+
+  /// The variables from which the template expands to `uri`, the reverse of `expand`.
+  ///
+  /// Values are percent-decoded. Only unambiguous templates are supported: an expression
+  /// may have one exploded variable and, unless it is a `;`, `?` or `&` expression, only as
+  /// its last variable. An empty value is indistinguishable from an undefined one and is
+  /// left out, a prefix modifier yields the truncated value, and a non-exploded composite
+  /// value comes back as a list.
+  ///
+  /// ```rust
+  /// use uri_rs::{TemplateValue, Uri, UriTemplate};
+  /// let template = UriTemplate::parse("https://api.x.com/repos{/owner,repo}/issues{?state}").unwrap();
+  /// let uri = Uri::parse("https://api.x.com/repos/j5ik2o/uri-rs/issues?state=open").unwrap();
+  /// let variables = template.match_uri(&uri).unwrap();
+  /// assert_eq!(variables["owner"], TemplateValue::from("j5ik2o"));
+  /// assert_eq!(variables["state"], TemplateValue::from("open"));
+  /// assert_eq!(template.expand(&variables).unwrap(), uri);
+  /// ```
+  pub fn match_uri(&self, uri: &Uri) -> Option<HashMap<String, TemplateValue>> {
+    let mut variables = HashMap::new();
+    if Self::match_parts(&self.parts, &uri.to_string(), &mut variables) {
+      Some(variables)
+    } else {
+      None
+    }
+  }
+
+  fn match_parts(
+    parts: &[TemplatePart],
+    text: &str,
+    variables: &mut HashMap<String, TemplateValue>,
+  ) -> bool {
+    let (part, rest) = match parts.split_first() {
+      Some(split) => split,
+      None => return text.is_empty(),
+    };
+    let expression = match part {
+      TemplatePart::Literal(s) => {
+        return match text.strip_prefix(encode(s, true).as_str()) {
+          Some(text) => Self::match_parts(rest, text, variables),
+          None => false,
+        }
+      }
+      TemplatePart::Expression(e) => e,
+    };
+    // Longest expansion first, backtracking to shorter ones.
+    let max_len = text
+      .find(|c| !expression.can_contain(c))
+      .unwrap_or(text.len());
+    let ends = (0..=max_len)
+      .rev()
+      .filter(|end| text.is_char_boundary(*end));
+    for end in ends {
+      let matched = match expression.match_text(&text[..end]) {
+        Some(matched) => matched,
+        None => continue,
+      };
+      let conflicts = matched
+        .iter()
+        .any(|(name, value)| matches!(variables.get(name), Some(v) if v != value));
+      if conflicts {
+        continue;
+      }
+      let added = matched
+        .into_iter()
+        .filter(|(name, _)| !variables.contains_key(name))
+        .collect_vec();
+      for (name, value) in added.iter() {
+        variables.insert(name.clone(), value.clone());
+      }
+      if Self::match_parts(rest, &text[end..], variables) {
+        return true;
+      }
+      for (name, _) in added.iter() {
+        variables.remove(name);
+      }
+    }
+    false
+  }
+}
+
+fn decode(s: &str) -> Option<String> {
+  percent_decode_str(s)
+    .decode_utf8()
+    .ok()
+    .map(|s| s.to_string())
+}
+
+// A non-exploded value, which is a list if it has an unencoded `,`.
+fn decode_value(s: &str, op: Operator) -> Option<TemplateValue> {
+  if !op.allows_reserved() && s.contains(',') {
+    s.split(',')
+      .map(decode)
+      .collect::<Option<Vec<_>>>()
+      .map(TemplateValue::List)
+  } else {
+    decode(s).map(TemplateValue::String)
+  }
+}
+
+// Percent-encodes all but unreserved characters and, if `allow_reserved`, reserved
+// characters and percent-encoded triplets.
+fn encode(s: &str, allow_reserved: bool) -> String {
+  let bytes = s.as_bytes();
+  let mut result = String::new();
+  for (idx, &b) in bytes.iter().enumerate() {
+    let c = b as char;
+    let is_triplet = b == b'%'
+      && bytes.len() > idx + 2
+      && bytes[idx + 1].is_ascii_hexdigit()
+      && bytes[idx + 2].is_ascii_hexdigit();
+    if c.is_ascii_alphanumeric()
+      || "-._~".contains(c)
+      || (allow_reserved && (":/?#[]@!$&'()*+,;=".contains(c) || is_triplet))
+    {
+      result.push(c)
+    } else {
+      result.push_str(&format!("%{:02X}", b))
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::{TemplateValue, Uri, UriTemplate, UriTemplateError};
+
+  // The variables of RFC 6570, section 3.2.
+  fn variables() -> HashMap<String, TemplateValue> {
+    let mut variables = HashMap::new();
+    let mut set = |name: &str, value: TemplateValue| {
+      variables.insert(name.to_string(), value);
+    };
+    set("count", vec!["one", "two", "three"].into());
+    set("dom", vec!["example", "com"].into());
+    set("dub", "me/too".into());
+    set("hello", "Hello World!".into());
+    set("half", "50%".into());
+    set("var", "value".into());
+    set("who", "fred".into());
+    set("base", "http://example.com/home/".into());
+    set("path", "/foo/bar".into());
+    set("list", vec!["red", "green", "blue"].into());
+    set(
+      "keys",
+      vec![("semi", ";"), ("dot", "."), ("comma", ",")].into(),
+    );
+    set("v", "6".into());
+    set("x", "1024".into());
+    set("y", "768".into());
+    set("empty", "".into());
+    set("empty_keys", TemplateValue::AssocArray(vec![]));
+    variables
+  }
+
+  fn assert_expansions(cases: &[(&str, &str)]) {
+    let variables = variables();
+    for (template, expected) in cases.iter() {
+      let template = UriTemplate::parse(template).unwrap();
+      assert_eq!(
+        template.expand_to_string(&variables),
+        *expected,
+        "{}",
+        template
+      );
+    }
+  }
+
+  #[test]
+  fn test_levels_1_to_3() {
+    assert_expansions(&[
+      ("{var}", "value"),
+      ("{hello}", "Hello%20World%21"),
+      ("{half}", "50%25"),
+      ("O{empty}X", "OX"),
+      ("O{undef}X", "OX"),
+      ("{x,y}", "1024,768"),
+      ("{x,hello,y}", "1024,Hello%20World%21,768"),
+      ("?{x,empty}", "?1024,"),
+      ("?{x,undef}", "?1024"),
+      ("?{undef,y}", "?768"),
+      ("{+var}", "value"),
+      ("{+hello}", "Hello%20World!"),
+      ("{+half}", "50%25"),
+      ("{base}index", "http%3A%2F%2Fexample.com%2Fhome%2Findex"),
+      ("{+base}index", "http://example.com/home/index"),
+      ("{+path}/here", "/foo/bar/here"),
+      ("here?ref={+path}", "here?ref=/foo/bar"),
+      ("up{+path}{var}/here", "up/foo/barvalue/here"),
+      ("{+x,hello,y}", "1024,Hello%20World!,768"),
+      ("{#var}", "#value"),
+      ("{#hello}", "#Hello%20World!"),
+      ("{#path,x}/here", "#/foo/bar,1024/here"),
+      ("X{.var}", "X.value"),
+      ("X{.x,y}", "X.1024.768"),
+      ("{/var}", "/value"),
+      ("{/var,x}/here", "/value/1024/here"),
+      ("{;x,y}", ";x=1024;y=768"),
+      ("{;x,y,empty}", ";x=1024;y=768;empty"),
+      ("{?x,y}", "?x=1024&y=768"),
+      ("{?x,y,empty}", "?x=1024&y=768&empty="),
+      ("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+      ("{&x,y,empty}", "&x=1024&y=768&empty="),
+    ]);
+  }
+
+  #[test]
+  fn test_level_4() {
+    assert_expansions(&[
+      ("{var:3}", "val"),
+      ("{var:30}", "value"),
+      ("{list}", "red,green,blue"),
+      ("{list*}", "red,green,blue"),
+      ("{keys}", "semi,%3B,dot,.,comma,%2C"),
+      ("{keys*}", "semi=%3B,dot=.,comma=%2C"),
+      ("{+path:6}/here", "/foo/b/here"),
+      ("{+list}", "red,green,blue"),
+      ("{+keys*}", "semi=;,dot=.,comma=,"),
+      ("{#keys}", "#semi,;,dot,.,comma,,"),
+      ("X{.list*}", "X.red.green.blue"),
+      ("X{.keys}", "X.semi,%3B,dot,.,comma,%2C"),
+      ("X{.empty_keys}", "X"),
+      ("{/var:1,var}", "/v/value"),
+      ("{/list*,path:4}", "/red/green/blue/%2Ffoo"),
+      ("{/keys*}", "/semi=%3B/dot=./comma=%2C"),
+      ("{;hello:5}", ";hello=Hello"),
+      ("{;list*}", ";list=red;list=green;list=blue"),
+      ("{;keys*}", ";semi=%3B;dot=.;comma=%2C"),
+      ("{?var:3}", "?var=val"),
+      ("{?list}", "?list=red,green,blue"),
+      ("{?list*}", "?list=red&list=green&list=blue"),
+      ("{?keys*}", "?semi=%3B&dot=.&comma=%2C"),
+      ("{&var:3}", "&var=val"),
+      ("{&keys*}", "&semi=%3B&dot=.&comma=%2C"),
+      ("{dub}{/count*}", "me%2Ftoo/one/two/three"),
+      ("{/who,dom}", "/fred/example,com"),
+    ]);
+  }
+
+  #[test]
+  fn test_expand_uri() {
+    let template = UriTemplate::parse("http://example.com/~{who}/caf\u{e9}{?q}").unwrap();
+    let mut variables = HashMap::new();
+    variables.insert("who".to_string(), TemplateValue::from("fred"));
+    variables.insert("q".to_string(), TemplateValue::from("a b"));
+    assert_eq!(
+      template.expand(&variables).unwrap().to_string(),
+      "http://example.com/~fred/caf%C3%A9?q=a%20b"
+    );
+    assert_eq!(template.variable_names(), vec!["who", "q"]);
+    let template = UriTemplate::parse("{scheme}://{host}/").unwrap();
+    assert!(matches!(
+      template.expand(&HashMap::new()),
+      Err(UriTemplateError::InvalidUri(_))
+    ));
+  }
+
+  #[test]
+  fn test_match_uri() {
+    let matched = |template: &str, uri: &str| {
+      let template = UriTemplate::parse(template).unwrap();
+      let uri = Uri::parse(uri).unwrap();
+      let variables = template.match_uri(&uri)?;
+      assert_eq!(template.expand(&variables).unwrap(), uri);
+      let mut variables = variables.into_iter().collect::<Vec<_>>();
+      variables.sort_by(|a, b| a.0.cmp(&b.0));
+      Some(variables)
+    };
+    let vars = |pairs: Vec<(&str, TemplateValue)>| {
+      Some(
+        pairs
+          .into_iter()
+          .map(|(k, v)| (k.to_string(), v))
+          .collect::<Vec<_>>(),
+      )
+    };
+    assert_eq!(
+      matched(
+        "http://example.com/users/{id}",
+        "http://example.com/users/fred%20x"
+      ),
+      vars(vec![("id", "fred x".into())])
+    );
+    assert_eq!(
+      matched(
+        "https://api.x.com/repos{/owner,repo}/issues{?state,labels*}",
+        "https://api.x.com/repos/j5ik2o/uri-rs/issues?labels=bug&labels=help%20wanted"
+      ),
+      vars(vec![
+        ("labels", vec!["bug", "help wanted"].into()),
+        ("owner", "j5ik2o".into()),
+        ("repo", "uri-rs".into()),
+      ])
+    );
+    assert_eq!(
+      matched("http://h/files{/path*}", "http://h/files/a/b/c.txt"),
+      vars(vec![("path", vec!["a", "b", "c.txt"].into())])
+    );
+    assert_eq!(
+      matched(
+        "http://h/search{?q,lang}{&page}",
+        "http://h/search?q=a,b&lang=en&page=2"
+      ),
+      vars(vec![
+        ("lang", "en".into()),
+        ("page", "2".into()),
+        ("q", vec!["a", "b"].into()),
+      ])
+    );
+    assert_eq!(
+      matched("http://h/search{?params*}", "http://h/search?a=1&b="),
+      vars(vec![("params", vec![("a", "1"), ("b", "")].into())])
+    );
+    assert_eq!(
+      matched("http://h/m{;x,y}", "http://h/m;y=768"),
+      vars(vec![("y", "768".into())])
+    );
+    assert_eq!(
+      matched(
+        "http://h/{dub}{/count*}{#frag}",
+        "http://h/me%2Ftoo/one/two#a/b"
+      ),
+      vars(vec![
+        ("count", vec!["one", "two"].into()),
+        ("dub", "me/too".into()),
+        ("frag", "a/b".into()),
+      ])
+    );
+    assert_eq!(
+      matched("http://h/{a}/{a}", "http://h/x/x"),
+      vars(vec![("a", "x".into())])
+    );
+    assert_eq!(
+      matched("http://h/users{/id}", "http://h/users"),
+      vars(vec![])
+    );
+    assert_eq!(matched("http://h/{a}/{a}", "http://h/x/y"), None);
+    assert_eq!(matched("http://h/users/{id}", "http://h/users/1/2"), None);
+    assert_eq!(matched("http://h/users{/id}", "http://h/users/1/2"), None);
+    assert_eq!(
+      matched("http://h/search{?q}", "http://h/search?q=a&x=1"),
+      None
+    );
+    assert_eq!(matched("http://h/users/{id}", "http://other/users/1"), None);
+  }
+}
@@ -1,6 +1,37 @@
 use std::fmt::Formatter;
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+/// The mask that replaces credentials in redacted output.
+pub const REDACTED: &str = "***";
+
+// userinfo = *( unreserved / pct-encoded / sub-delims / ":" ), where ":" ends the user name.
+const USER_NAME: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~')
+  .remove(b'!')
+  .remove(b'$')
+  .remove(b'&')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')')
+  .remove(b'*')
+  .remove(b'+')
+  .remove(b',')
+  .remove(b';')
+  .remove(b'=');
+
+const PASSWORD: &AsciiSet = &USER_NAME.remove(b':');
+
+/// User information of an authority, `userinfo = *( unreserved / pct-encoded / sub-delims / ":" )`.
+///
+/// The text is split at the first `:` into `user_name` and `password`, both kept as written,
+/// so `user:` has an empty password while `user` has none, and `:pw` has an empty user name.
+///
+/// `Debug` shows the password as `***`, so that logging a `Uri` does not leak it.
+#[derive(Clone, PartialEq, Hash)]
 pub struct UserInfo {
   user_name: String,
   password: Option<String>,
@@ -17,19 +48,19 @@ impl Default for UserInfo {
 
 impl std::fmt::Display for UserInfo {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(
-      f,
-      "{}{}",
-      self.user_name(),
-      self
-        .password
-        .iter()
-        .map(|s| format!(":{}", s))
-        .fold("".to_string(), |mut acc, s| {
-          acc.push_str(&s);
-          acc
-        })
-    )
+    match &self.password {
+      Some(password) => write!(f, "{}:{}", self.user_name, password),
+      None => write!(f, "{}", self.user_name),
+    }
+  }
+}
+
+impl std::fmt::Debug for UserInfo {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("UserInfo")
+      .field("user_name", &self.user_name)
+      .field("password", &self.password.as_ref().map(|_| REDACTED))
+      .finish()
   }
 }
 
@@ -39,17 +70,121 @@ impl From<(&str, Option<&str>)> for UserInfo {
   }
 }
 
+/// Takes userinfo text as written in a URI, without encoding it.
+impl From<&str> for UserInfo {
+  fn from(src: &str) -> Self {
+    match src.find(':') {
+      Some(idx) => Self {
+        user_name: src[..idx].to_string(),
+        password: Some(src[idx + 1..].to_string()),
+      },
+      None => Self {
+        user_name: src.to_string(),
+        password: None,
+      },
+    }
+  }
+}
+
+impl From<String> for UserInfo {
+  fn from(src: String) -> Self {
+    Self::from(src.as_str())
+  }
+}
+
 impl UserInfo {
+  /// Percent-encodes `user_name` and `password` and joins them with `:`. Everything but
+  /// unreserved characters and sub-delims is encoded, including `%`, and `:` is kept in the
+  /// password only.
   pub fn new(user_name: String, password: Option<String>) -> Self {
     Self {
-      user_name,
-      password,
+      user_name: utf8_percent_encode(&user_name, USER_NAME).to_string(),
+      password: password.map(|password| utf8_percent_encode(&password, PASSWORD).to_string()),
     }
   }
+
+  pub fn is_empty(&self) -> bool {
+    self.user_name.is_empty() && self.password.is_none()
+  }
+
+  /// The text before the first `:`, which may be empty.
   pub fn user_name(&self) -> &str {
     &self.user_name
   }
+
+  /// The text after the first `:`, `None` without a `:` and `Some("")` when nothing follows it.
   pub fn password(&self) -> Option<&String> {
     self.password.as_ref()
   }
+
+  /// A copy with the password, if any, replaced by `***`.
+  pub fn redacted(&self) -> UserInfo {
+    Self {
+      user_name: self.user_name.clone(),
+      password: self.password.as_ref().map(|_| REDACTED.to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Uri, UserInfo};
+
+  #[test]
+  fn test_split() {
+    let split = |s: &str| {
+      let ui = UserInfo::from(s);
+      (
+        ui.user_name().to_string(),
+        ui.password().map(|s| s.to_string()),
+      )
+    };
+    let owned = |u: &str, p: Option<&str>| (u.to_string(), p.map(|s| s.to_string()));
+    assert_eq!(split("user"), owned("user", None));
+    assert_eq!(split("user:"), owned("user", Some("")));
+    assert_eq!(split("user:pw"), owned("user", Some("pw")));
+    assert_eq!(split(":pw"), owned("", Some("pw")));
+    assert_eq!(split("a:b:c"), owned("a", Some("b:c")));
+    assert_eq!(split(""), owned("", None));
+    assert_eq!(split(":"), owned("", Some("")));
+  }
+
+  #[test]
+  fn test_new() {
+    assert_eq!(UserInfo::new("user".to_string(), None).to_string(), "user");
+    assert_eq!(
+      UserInfo::new("user".to_string(), Some("".to_string())).to_string(),
+      "user:"
+    );
+    assert_eq!(
+      UserInfo::new("".to_string(), Some("a:b".to_string())).to_string(),
+      ":a:b"
+    );
+    let ui = UserInfo::new("a:b".to_string(), None);
+    assert_eq!(ui.to_string(), "a%3Ab");
+    assert_eq!(ui.user_name(), "a%3Ab");
+    assert_eq!(ui.password(), None);
+    let ui = UserInfo::new("a:b".to_string(), Some("c:d".to_string()));
+    assert_eq!(ui.to_string(), "a%3Ab:c:d");
+    assert_eq!(ui.user_name(), "a%3Ab");
+    assert_eq!(ui.password().map(|s| s.as_str()), Some("c:d"));
+    assert_eq!(UserInfo::from("user:").redacted().to_string(), "user:***");
+    assert_eq!(UserInfo::from("user").redacted().to_string(), "user");
+    assert_eq!(
+      UserInfo::from("u%40ser:p%2F").redacted().to_string(),
+      "u%40ser:***"
+    );
+  }
+
+  #[test]
+  fn test_new_round_trip() {
+    let ui = UserInfo::new("a@b".to_string(), Some("p/w:?#%é".to_string()));
+    assert_eq!(ui.to_string(), "a%40b:p%2Fw:%3F%23%25%C3%A9");
+    let uri = Uri::parse(&format!("http://{}@h/", ui)).unwrap();
+    let authority = uri.authority().unwrap();
+    assert_eq!(authority.host_name().to_string(), "h");
+    assert_eq!(authority.user_info(), Some(&ui));
+    let ui = UserInfo::new("it's-me!".to_string(), None);
+    assert_eq!(ui.to_string(), "it's-me!");
+  }
 }
@@ -1,6 +1,6 @@
 use nom::{IResult, Needed, Compare, CompareResult, InputTake, AsBytes, InputLength};
 
-use nom::error::{ErrorKind, ContextError, ParseError};
+use nom::error::{ErrorKind, ContextError, FromExternalError, ParseError};
 
 use nom::lib::std::iter::{Copied, Enumerate};
 use nom::lib::std::ops::{Range, RangeFrom, RangeFull, RangeTo};
@@ -12,6 +12,7 @@ use std::fmt::Formatter;
 
 pub mod authority_parsers;
 pub mod basic_parsers;
+pub mod connection_parsers;
 pub mod fragment_parsers;
 pub mod hier_part_parsers;
 pub mod host_parsers;
@@ -22,6 +23,8 @@ pub mod port_parsers;
 pub mod query_parsers;
 pub mod scheme_parsers;
 pub mod uri_parsers;
+pub mod uri_template_parsers;
+pub mod urn_parsers;
 pub mod user_info_parsers;
 
 /// Custom Input Type
@@ -251,4 +254,12 @@ impl ParseError<Elms<'_>> for UriParseError {
   }
 }
 
+impl<E: std::fmt::Display> FromExternalError<Elms<'_>, E> for UriParseError {
+  fn from_external_error(input: Elms, kind: ErrorKind, e: E) -> Self {
+    let input = input.as_str().unwrap();
+    let message = format!("{:?}: {}:\t{:?}\n", kind, e, input);
+    Self { message }
+  }
+}
+
 pub type UResult<T, U> = IResult<T, U, UriParseError>;
@@ -1,5 +1,5 @@
 use nom::character::complete;
-use nom::combinator::{map, opt};
+use nom::combinator::{cut, map, opt};
 use nom::error::context;
 use nom::sequence::{preceded, terminated, tuple};
 
@@ -17,16 +17,16 @@ pub(crate) fn authority(i: Elms) -> UResult<Elms, Authority> {
           complete::char('@'),
         )),
         host_parsers::host_name,
-        opt(preceded(complete::char(':'), port_parsers::port)),
+        opt(preceded(complete::char(':'), cut(port_parsers::port))),
       )),
-      |(ui, h, p)| Authority::new(h, p, ui),
+      |(ui, h, p)| Authority::from_parts(h, p, ui),
     ),
   )(i)
 }
 
 #[cfg(test)]
 pub mod gens {
-  use prop_check_rs::gen::Gen;
+  use prop_check_rs::gen::{Gen, Gens};
 
   use crate::parser::parsers::basic_parsers::gens::to_option;
   use crate::parser::parsers::host_parsers::gens::host_gen;
@@ -35,7 +35,17 @@ pub mod gens {
 
   pub fn authority_gen() -> Gen<String> {
     let user_info_opt_gen = || to_option(|| user_info_gen());
-    let port_opt_gen = || to_option(|| port_gen());
+    let port_opt_gen = || {
+      to_option(|| {
+        Gens::one_bool().bind(|b| {
+          if b {
+            port_gen()
+          } else {
+            Gen::<String>::unit(|| "".to_string())
+          }
+        })
+      })
+    };
 
     user_info_opt_gen().bind(move |ui| {
       host_gen()
@@ -91,4 +101,27 @@ mod tests {
     );
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
+
+  #[test]
+  fn test_authority_with_empty_user_info() {
+    init();
+    for (s, user_info) in [
+      ("@h", Some("")),
+      (":pw@h:80", Some(":pw")),
+      ("user:@h", Some("user:")),
+      ("user:a:b@h", Some("user:a:b")),
+      ("h:80", None),
+    ]
+    .iter()
+    {
+      let (rest, a) = authority(Elms::new(s.as_bytes())).ok().unwrap();
+      assert!(rest.as_str().unwrap().is_empty());
+      assert_eq!(
+        a.user_info().map(|ui| ui.to_string()),
+        user_info.map(|s| s.to_string())
+      );
+      assert_eq!(a.host_name().to_string(), "h");
+      assert_eq!(a.to_string(), *s);
+    }
+  }
 }
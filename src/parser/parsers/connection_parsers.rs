@@ -0,0 +1,196 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete;
+use nom::character::complete::satisfy;
+use nom::combinator::{cut, eof, map, map_res, opt};
+use nom::error::context;
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{preceded, terminated, tuple};
+
+use crate::ast::host_name::HostName;
+use crate::ast::port::PortError;
+use crate::parser::parsers::{
+  Elms, path_parsers, port_parsers, query_parsers, scheme_parsers, UResult, user_info_parsers,
+};
+use crate::parser::parsers::basic_parsers::{is_sub_delims, pct_encoded, unreserved};
+use crate::parser::parsers::host_parsers::ip_literal;
+use crate::schemes::connection::ConnectionUri;
+
+// reg-name without ",", which separates hosts. IPv4 addresses are reg-names too.
+#[inline]
+fn reg_name_without_comma(i: Elms) -> UResult<Elms, String> {
+  map(
+    many1(alt((
+      map(unreserved, |c| c.into()),
+      pct_encoded,
+      map(satisfy(|c| is_sub_delims(c) && c != ','), |c| c.into()),
+    ))),
+    |sl| sl.into_iter().collect(),
+  )(i)
+}
+
+// host-port = ( IP-literal / reg-name ) [ ":" 1*DIGIT ], rejecting the empty port so that
+// the text round-trips
+#[inline]
+pub(crate) fn host_port(i: Elms) -> UResult<Elms, (HostName, Option<u16>)> {
+  context(
+    "host_port",
+    tuple((
+      map(alt((ip_literal, reg_name_without_comma)), HostName::new),
+      opt(preceded(
+        complete::char(':'),
+        cut(map_res(port_parsers::port, |p| {
+          p.number().ok_or(PortError::Empty)
+        })),
+      )),
+    )),
+  )(i)
+}
+
+// hosts = host-port *( "," host-port )
+#[inline]
+pub(crate) fn hosts(i: Elms) -> UResult<Elms, Vec<(HostName, Option<u16>)>> {
+  context("hosts", separated_list1(complete::char(','), host_port))(i)
+}
+
+// connection-URI = scheme "://" [ userinfo "@" ] hosts path-abempty [ "?" query ]
+#[inline]
+pub fn connection_uri(i: Elms) -> UResult<Elms, ConnectionUri> {
+  context(
+    "connection_uri",
+    map(
+      terminated(
+        tuple((
+          terminated(scheme_parsers::scheme, tag("://")),
+          opt(terminated(
+            user_info_parsers::user_info,
+            complete::char('@'),
+          )),
+          hosts,
+          path_parsers::path_abempty,
+          opt(preceded(complete::char('?'), query_parsers::query)),
+        )),
+        eof,
+      ),
+      |(s, ui, hosts, p, q)| ConnectionUri::new(s, ui, hosts, p, q),
+    ),
+  )(i)
+}
+
+#[cfg(test)]
+pub mod gens {
+  use itertools::Itertools;
+  use prop_check_rs::gen::{Gen, Gens};
+
+  use crate::parser::parsers::basic_parsers::gens::*;
+  use crate::parser::parsers::host_parsers::gens::ip_literal_str_gen;
+  use crate::parser::parsers::ipv4_address_parsers::gens::ipv4_address_str_gen;
+  use crate::parser::parsers::path_parsers::gens::path_abempty_str_gen;
+  use crate::parser::parsers::port_parsers::gens::port_gen;
+  use crate::parser::parsers::query_parsers::gens::query_gen;
+  use crate::parser::parsers::scheme_parsers::gens::scheme_gen;
+  use crate::parser::parsers::user_info_parsers::gens::user_info_gen;
+
+  pub fn host_port_str_gen() -> Gen<String> {
+    Gens::choose_u8(1, 3)
+      .bind(|n| match n {
+        1 => Gens::choose_u8(1, 10).bind(|len| unreserved_str_gen(len)),
+        2 => ipv4_address_str_gen(),
+        3 => ip_literal_str_gen(),
+        x => panic!("x = {}", x),
+      })
+      .bind(|host| {
+        to_option(|| port_gen()).fmap(move |port| match port {
+          Some(port) => format!("{}:{}", host, port),
+          None => host.clone(),
+        })
+      })
+  }
+
+  pub fn connection_uri_str_gen() -> Gen<String> {
+    scheme_gen().bind(|scheme| {
+      to_option(|| user_info_gen()).bind(move |ui| {
+        let prefix = format!(
+          "{}://{}",
+          scheme,
+          ui.map(|s| format!("{}@", s)).unwrap_or("".to_string())
+        );
+        Gens::choose_u8(1, 3)
+          .bind(|n| Gens::list_of_n(n as usize, || host_port_str_gen()))
+          .bind(move |hosts| {
+            let prefix = format!("{}{}", prefix, hosts.iter().join(","));
+            to_option(|| path_abempty_str_gen()).bind(move |path| {
+              let prefix = format!("{}{}", prefix, path.unwrap_or("".to_string()));
+              to_option(|| query_gen()).fmap(move |query| match query {
+                Some(query) => format!("{}?{}", prefix, query),
+                None => prefix.clone(),
+              })
+            })
+          })
+      })
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use anyhow::Result;
+  use prop_check_rs::prop;
+  use prop_check_rs::prop::TestCases;
+  use prop_check_rs::rng::RNG;
+
+  use super::*;
+  use super::gens::*;
+
+  const TEST_COUNT: TestCases = 100;
+
+  fn init() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+  }
+
+  #[test]
+  fn test_connection_uri() -> Result<()> {
+    init();
+    let mut counter = 0;
+    let prop = prop::for_all(
+      || connection_uri_str_gen(),
+      move |s| {
+        counter += 1;
+        log::debug!("{:>03}, connection_uri = {}", counter, s);
+        let (_, connection_uri) = connection_uri(Elms::new(s.as_bytes())).ok().unwrap();
+        assert_eq!(connection_uri.to_unredacted_string(), s);
+        true
+      },
+    );
+    prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
+  }
+
+  #[test]
+  fn test_hosts() {
+    init();
+    let (_, hosts) = hosts(Elms::new(b"h1:5432,[::1],10.0.0.1:27017"))
+      .ok()
+      .unwrap();
+    assert_eq!(
+      hosts,
+      vec![
+        (HostName::from("h1"), Some(5432)),
+        (HostName::from("[::1]"), None),
+        (HostName::from("10.0.0.1"), Some(27017)),
+      ]
+    );
+    assert!(connection_uri(Elms::new(b"mongodb://a,,b/")).is_err());
+    for s in ["mongodb://a:,b/", "mongodb://a,b:/", "mongodb://[::1]:/"].iter() {
+      let err = connection_uri(Elms::new(s.as_bytes())).err().unwrap();
+      assert!(
+        format!("{:?}", err).contains("empty port is not allowed"),
+        "{:?}",
+        err
+      );
+    }
+    assert!(connection_uri(Elms::new(b"mongodb://a,b#f")).is_err());
+  }
+}
@@ -2,16 +2,17 @@ use nom::branch::alt;
 use nom::character::complete::one_of;
 use nom::combinator::map;
 use nom::error::context;
-use nom::multi::many1;
+use nom::multi::many0;
 
 use crate::parser::parsers::{Elms, UResult};
 use crate::parser::parsers::basic_parsers::*;
 
+// fragment = *( pchar / "/" / "?" )
 #[inline]
 pub(crate) fn fragment(i: Elms) -> UResult<Elms, String> {
   context(
     "fragment",
-    map(many1(alt((pchar, map(one_of("/?"), |c| c.into())))), |sl| {
+    map(many0(alt((pchar, map(one_of("/?"), |c| c.into())))), |sl| {
       sl.into_iter().collect()
     }),
   )(i)
@@ -23,7 +24,7 @@ pub mod gens {
   use crate::parser::parsers::basic_parsers::gens::*;
 
   pub fn fragment_str_gen() -> Gen<String> {
-    rep_str_gen(1, u8::MAX - 1, || {
+    rep_str_gen(0, u8::MAX - 1, || {
       Gens::choose_u8(1, 2).bind(|n| match n {
         1 => pchar_str_gen(1, 1),
         2 => Gens::one_of_vec(vec!['/', '?']).fmap(|c| c.into()),
@@ -1,3 +1,4 @@
+use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::combinator::opt;
 use nom::sequence::{preceded, tuple};
@@ -25,6 +26,28 @@ pub fn hier_part(i: Elms) -> UResult<Elms, (Option<Authority>, Path)> {
   }
 }
 
+// relative-part = "//" authority path-abempty
+// / path-absolute
+// / path-noscheme
+// / path-empty
+#[inline]
+pub fn relative_part(i: Elms) -> UResult<Elms, (Option<Authority>, Path)> {
+  if let (i, Some((authority, path))) = opt(preceded(
+    tag("//"),
+    tuple((authority_parsers::authority, path_parsers::path_abempty)),
+  ))(i.clone())?
+  {
+    Ok((i, (Some(authority), path)))
+  } else {
+    let (i, path) = alt((
+      path_parsers::path_absolute,
+      path_parsers::path_no_scheme,
+      path_parsers::path_empty,
+    ))(i)?;
+    Ok((i, (None, path)))
+  }
+}
+
 #[cfg(test)]
 pub mod gens {
   use prop_check_rs::gen::{Gen, Gens};
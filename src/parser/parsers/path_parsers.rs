@@ -1,6 +1,7 @@
 use nom::branch::alt;
 use nom::character::complete;
-use nom::combinator::{eof, map, not, opt, value};
+use nom::character::complete::one_of;
+use nom::combinator::{eof, map, not, opt, peek, value};
 use nom::error::context;
 use nom::multi::{many0, many1};
 use nom::sequence::{preceded, tuple};
@@ -107,9 +108,17 @@ pub(crate) fn path_rootless(i: Elms) -> UResult<Elms, Path> {
   )(i)
 }
 
+// path-empty = 0<pchar>
+// Followed by the end of input or by a query or fragment.
 #[inline]
 pub(crate) fn path_empty(i: Elms) -> UResult<Elms, Path> {
-  context("path_empty", value(Path::of_empty(), eof))(i)
+  context(
+    "path_empty",
+    value(
+      Path::of_empty(),
+      alt((value((), eof), value((), peek(one_of("?#"))))),
+    ),
+  )(i)
 }
 
 #[inline]
@@ -118,7 +127,7 @@ pub(crate) fn path_without_abempty(i: Elms) -> UResult<Elms, Path> {
     .map(|(_, v)| v.is_some())?;
   let is_no_scheme =
     opt(segment_nz_nc)(i.clone()).map(|(_, v)| v.iter().any(|s| !s.contains(':')))?;
-  let is_empty = opt(eof)(i.clone()).map(|(_, v)| v.is_some())?;
+  let is_empty = opt(path_empty)(i.clone()).map(|(_, v)| v.is_some())?;
 
   log::debug!("is_absolute = {}", is_absolute);
   log::debug!("is_no_scheme = {}", is_no_scheme);
@@ -365,6 +374,33 @@ mod tests {
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
 
+  #[test]
+  fn test_path_segments() {
+    init();
+    let s = "/cars;color=red;year=2012/engine;;flag;k=v=w;e=";
+    let (_, r) = path_absolute(Elms::new(s.as_bytes())).ok().unwrap();
+    let segments = r.segments();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].name(), "cars");
+    assert_eq!(
+      segments[0].get_param("color"),
+      Some(vec![&"red".to_string()])
+    );
+    assert_eq!(
+      segments[0].get_param("year"),
+      Some(vec![&"2012".to_string()])
+    );
+    assert_eq!(segments[1].name(), "engine");
+    assert_eq!(segments[1].get_param("flag"), Some(vec![]));
+    assert_eq!(segments[1].get_param("k"), Some(vec![&"v=w".to_string()]));
+    assert_eq!(segments[1].get_param("missing"), None);
+    let joined = segments
+      .iter()
+      .map(|s| format!("/{}", s))
+      .collect::<String>();
+    assert_eq!(joined, s);
+  }
+
   #[test]
   fn test_path_without_abempty() -> Result<()> {
     init();
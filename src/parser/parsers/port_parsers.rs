@@ -1,19 +1,18 @@
 use std::str::FromStr;
 
-use nom::character::complete::digit1;
-use nom::combinator::map;
+use nom::character::complete::digit0;
+use nom::combinator::map_res;
 use nom::error::context;
 
+use crate::ast::port::Port;
 use crate::parser::parsers::{Elms, UResult};
 
+// port = *DIGIT
 #[inline]
-pub(crate) fn port(i: Elms) -> UResult<Elms, u16> {
+pub(crate) fn port(i: Elms) -> UResult<Elms, Port> {
   context(
     "port",
-    map(digit1, |e: Elms| {
-      let s = e.as_str().unwrap();
-      u16::from_str(s).unwrap()
-    }),
+    map_res(digit0, |e: Elms| Port::from_str(e.as_str().unwrap())),
   )(i)
 }
 
@@ -61,4 +60,23 @@ mod tests {
     );
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
+
+  #[test]
+  fn test_empty_and_out_of_range_port() {
+    init();
+    let (_, p) = port(Elms::new(b"")).ok().unwrap();
+    assert_eq!(p, Port::Empty);
+    let (_, p) = port(Elms::new(b"0")).ok().unwrap();
+    assert_eq!(p, Port::Number(0));
+    let (_, p) = port(Elms::new(b"65535")).ok().unwrap();
+    assert_eq!(p, Port::Number(65535));
+    for s in ["65536", "99999999999999999999"].iter() {
+      let err = port(Elms::new(s.as_bytes())).err().unwrap();
+      assert!(
+        format!("{:?}", err).contains(&format!("port out of range 0-65535: {}", s)),
+        "{:?}",
+        err
+      );
+    }
+  }
 }
@@ -33,9 +33,14 @@ pub fn query(i: Elms) -> UResult<Elms, Query> {
         many0(preceded(complete::char('&'), key_values())),
       )),
       |(head, tail)| {
-        let mut m = vec![head];
-        m.extend(tail);
-        Query::new(m)
+        if tail.is_empty() && head.0.is_empty() && head.1.is_none() {
+          // "?" followed by nothing: present but empty
+          Query::default()
+        } else {
+          let mut m = vec![head];
+          m.extend(tail);
+          Query::new(m)
+        }
       },
     ),
   )(i)
@@ -82,6 +87,16 @@ pub mod gens {
     })
     .fmap(|v| v.join("&"))
   }
+
+  pub fn query_with_empty_gen() -> Gen<String> {
+    Gens::one_bool().bind(|b| {
+      if b {
+        query_gen()
+      } else {
+        Gen::<String>::unit(|| "".to_string())
+      }
+    })
+  }
 }
 
 #[cfg(test)]
@@ -144,4 +159,12 @@ mod tests {
     );
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
+
+  #[test]
+  fn test_empty_query() {
+    init();
+    let (_, query) = query(Elms::new(b"")).ok().unwrap();
+    assert!(query.is_empty());
+    assert_eq!(query.to_string(), "");
+  }
 }
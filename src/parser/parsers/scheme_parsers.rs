@@ -1,6 +1,8 @@
 use nom::{AsChar, InputTakeAtPosition, IResult};
+use nom::character::complete::satisfy;
 use nom::combinator::map;
-use nom::error::{context, ErrorKind, ParseError};
+use nom::error::{context, ParseError};
+use nom::sequence::tuple;
 
 use crate::ast::scheme::Scheme;
 use crate::parser::parsers::{Elms, UResult};
@@ -11,18 +13,22 @@ where
   T: InputTakeAtPosition,
   <T as InputTakeAtPosition>::Item: AsChar,
 {
-  input.split_at_position1_complete(
-    |item| {
-      let c = item.as_char();
-      !(c == '+' || c == '-' || c == '.' || c.is_alphanumeric())
-    },
-    ErrorKind::Char,
-  )
+  input.split_at_position_complete(|item| {
+    let c = item.as_char();
+    !(c == '+' || c == '-' || c == '.' || c.is_ascii_alphanumeric())
+  })
 }
 
+// scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
 #[inline]
 pub(crate) fn scheme(i: Elms) -> UResult<Elms, Scheme> {
-  context("schema", map(code_point, |s: Elms| s.into()))(i)
+  context(
+    "schema",
+    map(
+      tuple((satisfy(|c| c.is_ascii_alphabetic()), code_point)),
+      |(head, tail): (char, Elms)| Scheme::new(format!("{}{}", head, tail)),
+    ),
+  )(i)
 }
 
 #[cfg(test)]
@@ -32,15 +38,18 @@ pub mod gens {
   use crate::parser::parsers::basic_parsers::gens::*;
 
   pub fn scheme_gen() -> Gen<String> {
-    rep_char_gen(5, || {
-      Gens::choose_u8(1, 5).bind(|n| match n {
-        1 => alpha_char_gen(),
-        2 => digit_gen('0', '9'),
-        3 => Gen::<char>::unit(|| '+'),
-        4 => Gen::<char>::unit(|| '-'),
-        5 => Gen::<char>::unit(|| '.'),
-        x => panic!("x = {}", x),
+    alpha_char_gen().bind(|head| {
+      rep_char_gen(5, || {
+        Gens::choose_u8(1, 5).bind(|n| match n {
+          1 => alpha_char_gen(),
+          2 => digit_gen('0', '9'),
+          3 => Gen::<char>::unit(|| '+'),
+          4 => Gen::<char>::unit(|| '-'),
+          5 => Gen::<char>::unit(|| '.'),
+          x => panic!("x = {}", x),
+        })
       })
+      .fmap(move |tail| format!("{}{}", head, tail))
     })
   }
 }
@@ -82,4 +91,15 @@ mod tests {
     );
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
+
+  #[test]
+  fn test_scheme_requires_leading_alpha() {
+    init();
+    for s in ["1http", "+http", ".http", "-http", "ｈttp", "éx", ""].iter() {
+      assert!(scheme(Elms::new(s.as_bytes())).is_err(), "{}", s);
+    }
+    let (rest, r) = scheme(Elms::new("h2é".as_bytes())).ok().unwrap();
+    assert_eq!(r.to_string(), "h2");
+    assert_eq!(rest.as_str().unwrap(), "é");
+  }
 }
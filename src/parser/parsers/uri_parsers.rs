@@ -1,9 +1,10 @@
 use nom::bytes::complete::tag;
 use nom::character::complete;
-use nom::combinator::{eof, map, opt};
+use nom::combinator::{eof, map, opt, verify};
 use nom::error::context;
 use nom::sequence::{preceded, terminated, tuple};
 
+use crate::ast::relative_ref::RelativeRef;
 use crate::ast::uri::Uri;
 use crate::parser::parsers::{
   Elms, fragment_parsers, hier_part_parsers, query_parsers, scheme_parsers, UResult,
@@ -58,33 +59,57 @@ pub fn uri(i: Elms) -> UResult<Elms, Uri> {
   )(i)
 }
 
+// URI whose host is a DNS name or an IP address when its scheme needs one
+#[inline]
+pub fn strict_uri(i: Elms) -> UResult<Elms, Uri> {
+  context("strict_uri", verify(uri, |u: &Uri| u.has_valid_host()))(i)
+}
+
+// relative-ref  = relative-part [ "?" query ] [ "#" fragment ]
+#[inline]
+pub fn relative_ref(i: Elms) -> UResult<Elms, RelativeRef> {
+  context(
+    "relative_ref",
+    map(
+      terminated(
+        tuple((
+          hier_part_parsers::relative_part,
+          opt(preceded(complete::char('?'), query_parsers::query)),
+          opt(preceded(complete::char('#'), fragment_parsers::fragment)),
+        )),
+        eof,
+      ),
+      |((a, p), q, f)| RelativeRef::new(a, p, q, f),
+    ),
+  )(i)
+}
+
 #[cfg(test)]
 pub mod gens {
   use prop_check_rs::gen::Gen;
 
+  use crate::parser::parsers::basic_parsers::gens::to_option;
   use crate::parser::parsers::fragment_parsers::gens::fragment_str_gen;
   use crate::parser::parsers::hier_part_parsers::gens::hier_part_gen;
-  use crate::parser::parsers::query_parsers::gens::query_gen;
+  use crate::parser::parsers::query_parsers::gens::query_with_empty_gen;
   use crate::parser::parsers::scheme_parsers::gens::scheme_gen;
   use crate::parser::parsers::path_parsers::gens::Pair;
 
   pub fn uri_gen() -> Gen<String> {
     scheme_gen().bind(|scheme| {
-      let base_gen = hier_part_gen()
-        .fmap(move |Pair(hier_part, is_empty)| (format!("{}:{}", scheme, hier_part), is_empty));
-      let query_gen = base_gen.bind(|(s, is_empty_opt)| {
-        if is_empty_opt.unwrap_or(false) {
-          Gen::<(String, Option<bool>)>::unit(|| (s.clone(), is_empty_opt))
-        } else {
-          query_gen().fmap(move |q| (format!("{}?{}", s, q), is_empty_opt))
-        }
+      let base_gen =
+        hier_part_gen().fmap(move |Pair(hier_part, _)| format!("{}:{}", scheme, hier_part));
+      let query_gen = base_gen.bind(|s| {
+        to_option(|| query_with_empty_gen()).fmap(move |q| match q {
+          Some(q) => format!("{}?{}", s, q),
+          None => s.clone(),
+        })
       });
-      let fragment_gen = query_gen.bind(|(s, is_empty_opt)| {
-        if is_empty_opt.unwrap_or(false) {
-          Gen::<(String, Option<bool>)>::unit(|| s.clone())
-        } else {
-          fragment_str_gen().fmap(move |f| format!("{}#{}", s, f))
-        }
+      let fragment_gen = query_gen.bind(|s| {
+        to_option(|| fragment_str_gen()).fmap(move |f| match f {
+          Some(f) => format!("{}#{}", s, f),
+          None => s.clone(),
+        })
       });
       fragment_gen
     })
@@ -126,4 +151,88 @@ mod tests {
     );
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
+
+  #[test]
+  fn test_empty_query_and_fragment() {
+    init();
+    let parse = |s: &str| uri(Elms::new(s.as_bytes())).ok().unwrap().1;
+
+    let without_query = parse("http://a/b");
+    let with_empty_query = parse("http://a/b?");
+    assert!(without_query.query().is_none());
+    assert!(with_empty_query.query().unwrap().is_empty());
+    assert_ne!(without_query, with_empty_query);
+    assert_eq!(with_empty_query.to_string(), "http://a/b?");
+
+    let with_empty_fragment = parse("http://x/#");
+    assert_eq!(with_empty_fragment.fragment().unwrap(), "");
+    assert_eq!(with_empty_fragment.to_string(), "http://x/#");
+
+    for s in ["http://a/b?#", "urn:?", "urn:#", "urn:?#"].iter() {
+      assert_eq!(parse(s).to_string(), *s);
+    }
+  }
+
+  #[test]
+  fn test_strict_uri() {
+    init();
+    for s in [
+      "http://example.com/",
+      "https://a-b.example.com.:8443/",
+      "ws://127.0.0.1/",
+      "wss://[::1]/",
+      "foo://!!!/",
+      "urn:isbn:0451450523",
+      "file:///etc/hosts",
+    ]
+    .iter()
+    {
+      assert!(strict_uri(Elms::new(s.as_bytes())).is_ok(), "{}", s);
+      assert!(uri(Elms::new(s.as_bytes())).is_ok(), "{}", s);
+    }
+    for s in [
+      "http:///",
+      "http://!!!/",
+      "https://a..b/",
+      "ftp://-a.com/",
+      "ws://a_b/",
+    ]
+    .iter()
+    {
+      assert!(strict_uri(Elms::new(s.as_bytes())).is_err(), "{}", s);
+      assert!(uri(Elms::new(s.as_bytes())).is_ok(), "{}", s);
+    }
+  }
+
+  #[test]
+  fn test_relative_ref() {
+    init();
+    for s in [
+      "",
+      "g",
+      "./g",
+      "g/",
+      "/g",
+      "//g",
+      "?y",
+      "g?y",
+      "#s",
+      "g#s",
+      "g?y#s",
+      ";x",
+      "g;x?y#s",
+      ".",
+      "../",
+      "../../g",
+      "//user@h:8080/p?q#f",
+      "?",
+      "#",
+    ]
+    .iter()
+    {
+      let (_, r) = relative_ref(Elms::new(s.as_bytes())).ok().unwrap();
+      assert_eq!(r.to_string(), *s);
+    }
+    assert!(relative_ref(Elms::new(b"g:h")).is_err());
+  }
 }
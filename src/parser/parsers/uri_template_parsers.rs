@@ -0,0 +1,287 @@
+use nom::{AsChar, InputTakeAtPosition, IResult};
+use nom::branch::alt;
+use nom::character::complete;
+use nom::character::complete::{one_of, satisfy};
+use nom::combinator::{eof, map, map_res, opt};
+use nom::error::{context, ErrorKind, ParseError};
+use nom::multi::{many0, many1, many_m_n, separated_list1};
+use nom::sequence::{delimited, preceded, terminated, tuple};
+
+use crate::ast::uri_template::{Expression, Modifier, Operator, TemplatePart, UriTemplate, VarSpec};
+use crate::parser::parsers::{Elms, UResult};
+use crate::parser::parsers::basic_parsers::*;
+
+// %x21 / %x23-24 / %x26 / %x28-3B / %x3D / %x3F-5B / %x5D / %x5F / %x61-7A / %x7E / ucschar / iprivate
+fn is_literal_char(c: char) -> bool {
+  match c as u32 {
+    0x21 | 0x23..=0x24 | 0x26 | 0x28..=0x3B | 0x3D | 0x3F..=0x5B | 0x5D | 0x5F | 0x61..=0x7A => {
+      true
+    }
+    0x7E => true,
+    n => n >= 0x80,
+  }
+}
+
+#[inline]
+fn literal_code_point<T, E: ParseError<T>>(input: T) -> IResult<T, T, E>
+where
+  T: InputTakeAtPosition,
+  <T as InputTakeAtPosition>::Item: AsChar,
+{
+  input.split_at_position1_complete(|item| !is_literal_char(item.as_char()), ErrorKind::Char)
+}
+
+// literals = 1*( literal-char / pct-encoded )
+#[inline]
+fn literals(i: Elms) -> UResult<Elms, String> {
+  context(
+    "literals",
+    map(
+      many1(alt((
+        map_res(literal_code_point, |s: Elms| s.as_string()),
+        pct_encoded,
+      ))),
+      |sl| sl.concat(),
+    ),
+  )(i)
+}
+
+// operator = "+" / "#" / "." / "/" / ";" / "?" / "&"
+#[inline]
+fn operator(i: Elms) -> UResult<Elms, Operator> {
+  map(one_of("+#./;?&"), |c| Operator::from_char(c).unwrap())(i)
+}
+
+// varchar = ALPHA / DIGIT / "_" / pct-encoded
+#[inline]
+fn varchar(i: Elms) -> UResult<Elms, String> {
+  alt((
+    map(satisfy(|c| c.is_ascii_alphanumeric() || c == '_'), |c| {
+      c.into()
+    }),
+    pct_encoded,
+  ))(i)
+}
+
+// varname = varchar *( ["."] varchar )
+#[inline]
+fn varname(i: Elms) -> UResult<Elms, String> {
+  map(
+    tuple((varchar, many0(tuple((opt(complete::char('.')), varchar))))),
+    |(head, tail)| {
+      let tail: String = tail
+        .into_iter()
+        .map(|(dot, s)| format!("{}{}", dot.map(|_| ".").unwrap_or(""), s))
+        .collect();
+      format!("{}{}", head, tail)
+    },
+  )(i)
+}
+
+// prefix = ":" max-length, max-length = %x31-39 0*3DIGIT
+#[inline]
+fn prefix(i: Elms) -> UResult<Elms, Modifier> {
+  map(
+    preceded(
+      complete::char(':'),
+      tuple((one_of("123456789"), many_m_n(0, 3, digit))),
+    ),
+    |(head, tail)| {
+      let len = tail
+        .into_iter()
+        .fold(head.to_digit(10).unwrap() as usize, |acc, c| {
+          acc * 10 + c.to_digit(10).unwrap() as usize
+        });
+      Modifier::Prefix(len)
+    },
+  )(i)
+}
+
+// varspec = varname [ modifier-level4 ], modifier-level4 = prefix / explode
+#[inline]
+fn varspec(i: Elms) -> UResult<Elms, VarSpec> {
+  map(
+    tuple((
+      varname,
+      opt(alt((
+        prefix,
+        map(complete::char('*'), |_| Modifier::Explode),
+      ))),
+    )),
+    |(name, modifier)| VarSpec::new(name, modifier),
+  )(i)
+}
+
+// expression = "{" [ operator ] variable-list "}", variable-list = varspec *( "," varspec )
+#[inline]
+fn expression(i: Elms) -> UResult<Elms, Expression> {
+  context(
+    "expression",
+    map(
+      delimited(
+        complete::char('{'),
+        tuple((opt(operator), separated_list1(complete::char(','), varspec))),
+        complete::char('}'),
+      ),
+      |(op, var_specs)| Expression::new(op.unwrap_or(Operator::Simple), var_specs),
+    ),
+  )(i)
+}
+
+// URI-Template = *( literals / expression )
+#[inline]
+pub fn uri_template(i: Elms) -> UResult<Elms, UriTemplate> {
+  context(
+    "uri_template",
+    map(
+      terminated(
+        many0(alt((
+          map(literals, TemplatePart::Literal),
+          map(expression, TemplatePart::Expression),
+        ))),
+        eof,
+      ),
+      UriTemplate::new,
+    ),
+  )(i)
+}
+
+#[cfg(test)]
+pub mod gens {
+  use itertools::Itertools;
+  use prop_check_rs::gen::{Gen, Gens};
+
+  use crate::parser::parsers::basic_parsers::gens::*;
+
+  fn literal_str_gen() -> Gen<String> {
+    rep_str_gen(1, 5, || {
+      Gens::choose_u8(1, 4).bind(|n| match n {
+        1 => unreserved_char_gen().fmap(|c| c.into()),
+        2 => pct_encoded_str_gen(),
+        3 => Gens::one_of_vec(vec![
+          ':', '/', '?', '#', '[', ']', '@', '!', '$', '&', '(', ')',
+        ])
+        .fmap(|c| c.into()),
+        4 => Gens::one_of_vec(vec!["é", "日本"]).fmap(|s| s.to_string()),
+        x => panic!("x = {}", x),
+      })
+    })
+  }
+
+  fn varname_gen() -> Gen<String> {
+    Gens::list_of_n(2, || {
+      rep_char_gen(3, || {
+        Gens::choose_u8(1, 3).bind(|n| match n {
+          1 => alpha_char_gen(),
+          2 => digit_gen('0', '9'),
+          3 => Gen::<char>::unit(|| '_'),
+          x => panic!("x = {}", x),
+        })
+      })
+    })
+    .bind(|parts| {
+      Gens::one_bool().fmap(move |b| if b { parts.join(".") } else { parts[0].clone() })
+    })
+  }
+
+  fn varspec_gen() -> Gen<String> {
+    varname_gen().bind(|name| {
+      Gens::choose_u16(0, 1200).fmap(move |n| match n {
+        0..=399 => name.clone(),
+        400..=799 => format!("{}*", name),
+        len => format!("{}:{}", name, len - 799),
+      })
+    })
+  }
+
+  fn expression_gen() -> Gen<String> {
+    Gens::one_of_vec(vec!["", "+", "#", ".", "/", ";", "?", "&"]).bind(|op| {
+      Gens::choose_u8(1, 3).bind(move |n| {
+        Gens::list_of_n(n as usize, || varspec_gen())
+          .fmap(move |specs| format!("{{{}{}}}", op, specs.iter().join(",")))
+      })
+    })
+  }
+
+  pub fn uri_template_gen() -> Gen<String> {
+    Gens::choose_u8(0, 4).bind(|n| {
+      Gens::list_of_n(n as usize, || {
+        Gens::one_bool().bind(|b| {
+          if b {
+            literal_str_gen()
+          } else {
+            expression_gen()
+          }
+        })
+      })
+      .fmap(|parts| parts.concat())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use anyhow::Result;
+  use prop_check_rs::prop;
+  use prop_check_rs::prop::TestCases;
+  use prop_check_rs::rng::RNG;
+
+  use super::*;
+  use super::gens::*;
+
+  const TEST_COUNT: TestCases = 100;
+
+  fn init() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+  }
+
+  #[test]
+  fn test_uri_template() -> Result<()> {
+    init();
+    let mut counter = 0;
+    let prop = prop::for_all(
+      || uri_template_gen(),
+      move |s| {
+        counter += 1;
+        log::debug!("{:>03}, uri_template = {}", counter, s);
+        let (_, r) = uri_template(Elms::new(s.as_bytes())).ok().unwrap();
+        assert_eq!(r.to_string(), s);
+        true
+      },
+    );
+    prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
+  }
+
+  #[test]
+  fn test_invalid_templates() {
+    init();
+    for s in [
+      "{",
+      "}",
+      "{}",
+      "{var",
+      "{var}}",
+      "{=var}",
+      "{!var}",
+      "{|var}",
+      "{var:0}",
+      "{var:10000}",
+      "{var*:3}",
+      "{a..b}",
+      "{.a.}",
+      "{a,}",
+      "{a b}",
+      "a b",
+      "a<b",
+      "50%",
+      "%zz",
+    ]
+    .iter()
+    {
+      assert!(uri_template(Elms::new(s.as_bytes())).is_err(), "{}", s);
+    }
+  }
+}
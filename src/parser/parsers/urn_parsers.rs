@@ -0,0 +1,257 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case, take_while_m_n};
+use nom::character::complete;
+use nom::character::complete::one_of;
+use nom::combinator::{eof, map, not, opt, verify};
+use nom::error::context;
+use nom::multi::many0;
+use nom::sequence::{preceded, terminated, tuple};
+use nom::AsChar;
+
+use crate::parser::parsers::{Elms, fragment_parsers, UResult};
+use crate::parser::parsers::basic_parsers::pchar;
+use crate::schemes::urn::Urn;
+
+// NID = (alphanum) 0*30(ldh) (alphanum)
+#[inline]
+pub(crate) fn nid(i: Elms) -> UResult<Elms, String> {
+  context(
+    "nid",
+    verify(
+      map(
+        take_while_m_n(2, 32, |c: u8| c.is_alphanum() || c == b'-'),
+        |s: Elms| s.as_string().unwrap(),
+      ),
+      |s: &str| !s.starts_with('-') && !s.ends_with('-'),
+    ),
+  )(i)
+}
+
+// NSS = pchar *(pchar / "/")
+#[inline]
+pub(crate) fn nss(i: Elms) -> UResult<Elms, String> {
+  context(
+    "nss",
+    map(
+      tuple((
+        pchar,
+        many0(alt((pchar, map(complete::char('/'), |c| c.into())))),
+      )),
+      |(head, tail)| {
+        let mut s = head;
+        s.extend(tail);
+        s
+      },
+    ),
+  )(i)
+}
+
+// r-component = pchar *( pchar / "/" / "?" )
+// A "?" is not taken when it starts a q-component ("?=").
+#[inline]
+fn r_component(i: Elms) -> UResult<Elms, String> {
+  map(
+    tuple((
+      pchar,
+      many0(alt((
+        pchar,
+        map(complete::char('/'), |c| c.into()),
+        map(
+          terminated(complete::char('?'), not(complete::char('='))),
+          |c| c.into(),
+        ),
+      ))),
+    )),
+    |(head, tail)| {
+      let mut s = head;
+      s.extend(tail);
+      s
+    },
+  )(i)
+}
+
+// q-component = pchar *( pchar / "/" / "?" )
+#[inline]
+fn q_component(i: Elms) -> UResult<Elms, String> {
+  map(
+    tuple((pchar, many0(alt((pchar, map(one_of("/?"), |c| c.into())))))),
+    |(head, tail)| {
+      let mut s = head;
+      s.extend(tail);
+      s
+    },
+  )(i)
+}
+
+// namestring    = assigned-name
+//                 [ rq-components ]
+//                 [ "#" f-component ]
+// assigned-name = "urn" ":" NID ":" NSS
+// rq-components = [ "?+" r-component ]
+//                 [ "?=" q-component ]
+#[inline]
+pub fn urn(i: Elms) -> UResult<Elms, Urn> {
+  context(
+    "urn",
+    map(
+      terminated(
+        tuple((
+          preceded(tag_no_case("urn:"), terminated(nid, complete::char(':'))),
+          nss,
+          opt(preceded(tag("?+"), r_component)),
+          opt(preceded(tag("?="), q_component)),
+          opt(preceded(complete::char('#'), fragment_parsers::fragment)),
+        )),
+        eof,
+      ),
+      |(nid, nss, r, q, f)| Urn::new(nid, nss, r, q, f),
+    ),
+  )(i)
+}
+
+#[cfg(test)]
+pub mod gens {
+  use prop_check_rs::gen::{Gen, Gens};
+
+  use crate::parser::parsers::basic_parsers::gens::*;
+  use crate::parser::parsers::fragment_parsers::gens::fragment_str_gen;
+
+  fn alphanum_char_gen() -> Gen<char> {
+    Gens::one_bool().bind(|b| {
+      if b {
+        alpha_char_gen()
+      } else {
+        digit_gen('0', '9')
+      }
+    })
+  }
+
+  pub fn nid_str_gen() -> Gen<String> {
+    let ldh_gen = || {
+      Gens::choose_u8(0, 30).bind(|len| {
+        Gens::list_of_n(len as usize, || {
+          Gens::one_bool().bind(|b| {
+            if b {
+              alphanum_char_gen()
+            } else {
+              Gen::<char>::unit(|| '-')
+            }
+          })
+        })
+        .fmap(|cl| cl.into_iter().collect::<String>())
+      })
+    };
+    alphanum_char_gen().bind(move |head| {
+      ldh_gen()
+        .bind(move |ldh| alphanum_char_gen().fmap(move |last| format!("{}{}{}", head, ldh, last)))
+    })
+  }
+
+  pub fn nss_str_gen() -> Gen<String> {
+    pchar_str_gen(1, 10).bind(|head| {
+      rep_str_gen(0, 3, || pchar_str_gen(0, 10).fmap(|s| format!("/{}", s)))
+        .fmap(move |tail| format!("{}{}", head, tail))
+    })
+  }
+
+  pub fn urn_str_gen() -> Gen<String> {
+    let component_gen = || {
+      to_option(|| {
+        pchar_str_gen(1, 10).bind(|head| {
+          rep_str_gen(0, 3, || {
+            Gens::one_bool().bind(|b| {
+              if b {
+                pchar_str_gen(0, 5).fmap(|s| format!("/{}", s))
+              } else {
+                unreserved_str_gen(5).fmap(|s| format!("?{}", s))
+              }
+            })
+          })
+          .fmap(move |tail| format!("{}{}", head, tail))
+        })
+      })
+    };
+    nid_str_gen().bind(move |nid| {
+      nss_str_gen().bind(move |nss| {
+        let assigned_name = format!("urn:{}:{}", nid, nss);
+        component_gen().bind(move |r| {
+          let assigned_name = assigned_name.clone();
+          component_gen().bind(move |q| {
+            let prefix = format!(
+              "{}{}{}",
+              assigned_name,
+              r.as_ref()
+                .map(|s| format!("?+{}", s))
+                .unwrap_or("".to_string()),
+              q.as_ref()
+                .map(|s| format!("?={}", s))
+                .unwrap_or("".to_string())
+            );
+            to_option(|| fragment_str_gen()).fmap(move |f| {
+              format!(
+                "{}{}",
+                prefix,
+                f.map(|s| format!("#{}", s)).unwrap_or("".to_string())
+              )
+            })
+          })
+        })
+      })
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use anyhow::Result;
+  use prop_check_rs::prop;
+  use prop_check_rs::prop::TestCases;
+  use prop_check_rs::rng::RNG;
+
+  use super::*;
+  use super::gens::*;
+
+  const TEST_COUNT: TestCases = 100;
+
+  fn init() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+  }
+
+  #[test]
+  fn test_urn() -> Result<()> {
+    init();
+    let mut counter = 0;
+    let prop = prop::for_all(
+      || urn_str_gen(),
+      move |s| {
+        counter += 1;
+        log::debug!("{:>03}, urn = {}", counter, s);
+        let (_, urn) = urn(Elms::new(s.as_bytes())).ok().unwrap();
+        assert_eq!(urn.to_string(), s);
+        true
+      },
+    );
+    prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
+  }
+
+  #[test]
+  fn test_invalid_nid() {
+    init();
+    for s in [
+      "urn:a:b",
+      "urn:-ab:c",
+      "urn:ab-:c",
+      "urn:a_b:c",
+      "urn:abcdefghijklmnopqrstuvwxyz0123456:c",
+      "urn:isbn:",
+      "urn:isbn",
+    ]
+    .iter()
+    {
+      assert!(urn(Elms::new(s.as_bytes())).is_err(), "{}", s);
+    }
+  }
+}
@@ -1,34 +1,26 @@
 use nom::branch::alt;
 use nom::character::complete;
-use nom::combinator::{map, opt};
+use nom::combinator::map;
 use nom::error::context;
-use nom::multi::many1;
-use nom::sequence::{preceded, tuple};
+use nom::multi::many0;
 
 use crate::ast::user_info::UserInfo;
 use crate::parser::parsers::{Elms, UResult};
 use crate::parser::parsers::basic_parsers::{pct_encoded, sub_delims, unreserved};
 
-#[inline]
-fn code_point(i: Elms) -> UResult<Elms, String> {
-  map(
-    many1(alt((
-      map(unreserved, |c| c.into()),
-      pct_encoded,
-      map(sub_delims, |c| c.into()),
-    ))),
-    |sl| sl.into_iter().collect(),
-  )(i)
-}
-
-// *( unreserved / pct-encoded / sub-delims / ":" )
+// userinfo = *( unreserved / pct-encoded / sub-delims / ":" )
 #[inline]
 pub(crate) fn user_info(i: Elms) -> UResult<Elms, UserInfo> {
   context(
     "user_info",
     map(
-      tuple((code_point, opt(preceded(complete::char(':'), code_point)))),
-      |(user_name, password)| (user_name.as_str(), password.as_ref().map(|s| s.as_str())).into(),
+      many0(alt((
+        map(unreserved, |c| c.into()),
+        pct_encoded,
+        map(sub_delims, |c| c.into()),
+        map(complete::char(':'), |c| c.into()),
+      ))),
+      |sl: Vec<String>| UserInfo::from(sl.concat()),
     ),
   )(i)
 }
@@ -40,22 +32,14 @@ pub mod gens {
   use crate::parser::parsers::basic_parsers::gens::*;
 
   pub fn user_info_gen() -> Gen<String> {
-    let gen = || {
-      rep_str_gen(1, 5, || {
-        Gens::choose_u8(1, 3).bind(|n| match n {
-          1 => unreserved_str_gen(1),
-          2 => pct_encoded_str_gen(),
-          3 => sub_delims_str_gen(1),
-          x => panic!("x = {}", x),
-        })
+    rep_str_gen(0, 10, || {
+      Gens::choose_u8(1, 4).bind(|n| match n {
+        1 => unreserved_str_gen(1),
+        2 => pct_encoded_str_gen(),
+        3 => sub_delims_str_gen(1),
+        4 => Gen::<String>::unit(|| ":".to_string()),
+        x => panic!("x = {}", x),
       })
-    };
-    Gens::one_bool().bind(move |b| {
-      if b {
-        gen().bind(move |s1| gen().fmap(move |s2| format!("{}:{}", s1, s2)))
-      } else {
-        gen().fmap(|s| format!("{}", s))
-      }
     })
   }
 }
@@ -95,4 +79,23 @@ mod tests {
     );
     prop::test_with_prop(prop, 5, TEST_COUNT, RNG::new())
   }
+
+  #[test]
+  fn test_empty_user_and_password() {
+    init();
+    for (s, user_name, password) in [
+      ("", "", None),
+      (":pw", "", Some("pw")),
+      ("user:", "user", Some("")),
+      ("user:a:b", "user", Some("a:b")),
+    ]
+    .iter()
+    {
+      let (rest, r) = user_info(Elms::new(s.as_bytes())).ok().unwrap();
+      assert!(rest.as_str().unwrap().is_empty());
+      assert_eq!(r.user_name(), *user_name);
+      assert_eq!(r.password().map(|s| s.as_str()), *password);
+      assert_eq!(r.to_string(), *s);
+    }
+  }
 }
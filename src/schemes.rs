@@ -0,0 +1,59 @@
+use std::fmt::Formatter;
+
+use percent_encoding::percent_decode_str;
+
+use crate::ast::scheme::{KnownScheme, Scheme};
+
+pub mod connection;
+pub mod data;
+pub mod file;
+pub mod git;
+pub mod mailto;
+pub mod tel;
+pub mod urn;
+pub mod websocket;
+
+/// Error raised when a `Uri` cannot be viewed as a scheme-specific URI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemeError {
+  UnexpectedScheme {
+    expected: KnownScheme,
+    actual: Scheme,
+  },
+  InvalidSyntax(String),
+  InvalidEncoding(String),
+  InvalidFilePath(String),
+}
+
+impl std::fmt::Display for SchemeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SchemeError::UnexpectedScheme { expected, actual } => {
+        write!(f, "expected scheme {}, but got {}", expected, actual)
+      }
+      SchemeError::InvalidSyntax(msg) => write!(f, "invalid syntax: {}", msg),
+      SchemeError::InvalidEncoding(msg) => write!(f, "invalid encoding: {}", msg),
+      SchemeError::InvalidFilePath(msg) => write!(f, "invalid file path: {}", msg),
+    }
+  }
+}
+
+impl std::error::Error for SchemeError {}
+
+pub(crate) fn expect_scheme(actual: &Scheme, expected: KnownScheme) -> Result<(), SchemeError> {
+  if actual.known() == expected {
+    Ok(())
+  } else {
+    Err(SchemeError::UnexpectedScheme {
+      expected,
+      actual: actual.clone(),
+    })
+  }
+}
+
+pub(crate) fn percent_decode(s: &str) -> Result<String, SchemeError> {
+  percent_decode_str(s)
+    .decode_utf8()
+    .map(|s| s.to_string())
+    .map_err(|e| SchemeError::InvalidEncoding(format!("{}: {:?}", e, s)))
+}
@@ -0,0 +1,350 @@
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, percent_encode};
+
+use crate::ast::path::Path;
+use crate::ast::scheme::{KnownScheme, Scheme};
+use crate::ast::uri::Uri;
+use crate::schemes::{expect_scheme, percent_decode, SchemeError};
+
+// Everything but pchar and "/" is encoded, so the payload stays inside the path.
+const DATA: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~')
+  .remove(b'!')
+  .remove(b'$')
+  .remove(b'&')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')')
+  .remove(b'*')
+  .remove(b'+')
+  .remove(b',')
+  .remove(b';')
+  .remove(b'=')
+  .remove(b':')
+  .remove(b'@')
+  .remove(b'/');
+
+const PARAM: &AsciiSet = &DATA.add(b',').add(b';').add(b'=');
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const DEFAULT_MIME_TYPE: &str = "text/plain";
+const DEFAULT_CHARSET: &str = "US-ASCII";
+
+/// A `data:` URI (RFC 2397).
+///
+/// ```rust
+/// use uri_rs::{DataUri, Uri};
+/// let uri = Uri::parse("data:text/plain;charset=utf-8;base64,SGVsbG8sIFdvcmxkIQ==").unwrap();
+/// let data = DataUri::from_uri(&uri).unwrap();
+/// assert_eq!(data.mime_type(), "text/plain");
+/// assert_eq!(data.charset(), Some("utf-8"));
+/// assert_eq!(data.decode().unwrap(), b"Hello, World!".to_vec());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataUri {
+  mime_type: String,
+  parameters: Vec<(String, String)>,
+  base64: bool,
+  data: String,
+}
+
+impl Default for DataUri {
+  fn default() -> Self {
+    DataUri {
+      mime_type: String::default(),
+      parameters: Vec::default(),
+      base64: bool::default(),
+      data: String::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for DataUri {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.to_uri())
+  }
+}
+
+impl DataUri {
+  /// Encodes `data` as a percent-encoded or base64 payload.
+  pub fn new(
+    mime_type: String,
+    parameters: Vec<(String, String)>,
+    data: &[u8],
+    base64: bool,
+  ) -> Self {
+    let data = if base64 {
+      base64_encode(data)
+    } else {
+      percent_encode(data, DATA).to_string()
+    };
+    Self {
+      mime_type,
+      parameters,
+      base64,
+      data,
+    }
+  }
+
+  pub fn parse(text: &str) -> Result<DataUri, SchemeError> {
+    let uri = Uri::parse(text).map_err(|e| SchemeError::InvalidSyntax(format!("{:?}", e)))?;
+    Self::from_uri(&uri)
+  }
+
+  pub fn from_uri(uri: &Uri) -> Result<DataUri, SchemeError> {
+    expect_scheme(uri.schema(), KnownScheme::Data)?;
+    if uri.authority().is_some() {
+      return Err(SchemeError::InvalidSyntax(
+        "a data URI has no authority".to_string(),
+      ));
+    }
+    let text = format!(
+      "{}{}",
+      uri.path(),
+      uri
+        .query()
+        .map(|q| format!("?{}", q))
+        .unwrap_or("".to_string())
+    );
+    let (header, data) = match text.find(',') {
+      Some(idx) => (&text[..idx], &text[idx + 1..]),
+      None => {
+        return Err(SchemeError::InvalidSyntax(format!(
+          "missing ',' in data URI: {:?}",
+          text
+        )))
+      }
+    };
+    let mut items = header.split(';').collect_vec();
+    let base64 = items.len() > 1
+      && items
+        .last()
+        .map(|s| s.eq_ignore_ascii_case("base64"))
+        .unwrap_or(false);
+    if base64 {
+      items.pop();
+    }
+    let mime_type = percent_decode(items[0])?;
+    let mut parameters = Vec::new();
+    for item in &items[1..] {
+      let (name, value) = match item.find('=') {
+        Some(idx) => (&item[..idx], &item[idx + 1..]),
+        None => (&item[..], ""),
+      };
+      parameters.push((percent_decode(name)?, percent_decode(value)?));
+    }
+    Ok(Self {
+      mime_type,
+      parameters,
+      base64,
+      data: data.to_string(),
+    })
+  }
+
+  /// The MIME type, `text/plain` when the media type is omitted.
+  pub fn mime_type(&self) -> &str {
+    if self.mime_type.is_empty() {
+      DEFAULT_MIME_TYPE
+    } else {
+      &self.mime_type
+    }
+  }
+
+  pub fn parameters(&self) -> &Vec<(String, String)> {
+    &self.parameters
+  }
+
+  pub fn parameter(&self, name: &str) -> Option<&str> {
+    self
+      .parameters
+      .iter()
+      .find(|(n, _)| n.eq_ignore_ascii_case(name))
+      .map(|(_, v)| v.as_str())
+  }
+
+  /// The `charset` parameter, `US-ASCII` when the media type is omitted.
+  pub fn charset(&self) -> Option<&str> {
+    match self.parameter("charset") {
+      Some(charset) => Some(charset),
+      None if self.mime_type.is_empty() => Some(DEFAULT_CHARSET),
+      None => None,
+    }
+  }
+
+  pub fn is_base64(&self) -> bool {
+    self.base64
+  }
+
+  /// The payload as it appears in the URI.
+  pub fn data(&self) -> &str {
+    &self.data
+  }
+
+  pub fn decode(&self) -> Result<Vec<u8>, SchemeError> {
+    let bytes = percent_decode_str(&self.data).collect_vec();
+    if self.base64 {
+      base64_decode(&bytes)
+    } else {
+      Ok(bytes)
+    }
+  }
+
+  pub fn to_uri(&self) -> Uri {
+    let header = std::iter::once(percent_encode(self.mime_type.as_bytes(), DATA).to_string())
+      .chain(self.parameters.iter().map(|(name, value)| {
+        format!(
+          "{}={}",
+          percent_encode(name.as_bytes(), PARAM),
+          percent_encode(value.as_bytes(), PARAM)
+        )
+      }))
+      .chain(if self.base64 {
+        Some("base64".to_string())
+      } else {
+        None
+      })
+      .join(";");
+    let text = format!("{},{}", header, self.data);
+    let parts = text.split('/').map(|s| s.to_string()).collect_vec();
+    Uri::new(
+      Scheme::from(KnownScheme::Data),
+      None,
+      Path::of_rootless_from_strings(&parts),
+      None,
+      None,
+    )
+  }
+}
+
+// `usize::div_ceil` is not stable on the pinned toolchain, which does not know the lint either.
+#[allow(unknown_lints, clippy::manual_div_ceil)]
+fn base64_encode(data: &[u8]) -> String {
+  let mut result = String::with_capacity((data.len() + 2) / 3 * 4);
+  for chunk in data.chunks(3) {
+    let n = chunk
+      .iter()
+      .enumerate()
+      .fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+    for i in 0..4 {
+      if i <= chunk.len() {
+        result.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+      } else {
+        result.push('=');
+      }
+    }
+  }
+  result
+}
+
+fn base64_decode(data: &[u8]) -> Result<Vec<u8>, SchemeError> {
+  let error = || SchemeError::InvalidEncoding("invalid base64 payload".to_string());
+  let data = data
+    .iter()
+    .filter(|b| !b.is_ascii_whitespace())
+    .collect_vec();
+  let padding = data.iter().rev().take_while(|b| ***b == b'=').count();
+  if padding > 2 {
+    return Err(error());
+  }
+  let data = &data[..data.len() - padding];
+  if data.len() % 4 == 1 {
+    return Err(error());
+  }
+  let mut result = Vec::with_capacity(data.len() * 3 / 4);
+  for chunk in data.chunks(4) {
+    let mut n = 0u32;
+    for (i, b) in chunk.iter().enumerate() {
+      let v = BASE64_CHARS
+        .iter()
+        .position(|c| c == *b)
+        .ok_or_else(error)?;
+      n |= (v as u32) << (18 - 6 * i);
+    }
+    for i in 0..chunk.len() - 1 {
+      result.push((n >> (16 - 8 * i)) as u8);
+    }
+  }
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{DataUri, SchemeError, Uri};
+
+  #[test]
+  fn test_percent_encoded() {
+    let data = DataUri::parse("data:,Hello%2C%20World%21").unwrap();
+    assert_eq!(data.mime_type(), "text/plain");
+    assert_eq!(data.charset(), Some("US-ASCII"));
+    assert!(!data.is_base64());
+    assert_eq!(data.decode().unwrap(), b"Hello, World!".to_vec());
+
+    let data = DataUri::parse("data:text/html;charset=utf-8,%3Ch1%3EHi?%3C/h1%3E").unwrap();
+    assert_eq!(data.mime_type(), "text/html");
+    assert_eq!(data.parameter("Charset"), Some("utf-8"));
+    assert_eq!(data.decode().unwrap(), b"<h1>Hi?</h1>".to_vec());
+  }
+
+  #[test]
+  fn test_base64() {
+    let data = DataUri::parse("data:image/png;base64,iVBORw0KGgo=").unwrap();
+    assert_eq!(data.mime_type(), "image/png");
+    assert_eq!(data.charset(), None);
+    assert!(data.is_base64());
+    assert_eq!(
+      data.decode().unwrap(),
+      vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]
+    );
+
+    let data = DataUri::parse("data:;base64,YQ").unwrap();
+    assert_eq!(data.decode().unwrap(), b"a".to_vec());
+
+    let data = DataUri::parse("data:;base64,Y!==").unwrap();
+    assert!(matches!(
+      data.decode(),
+      Err(SchemeError::InvalidEncoding(_))
+    ));
+  }
+
+  #[test]
+  fn test_encode() {
+    let payloads: [&[u8]; 5] = [b"", b"a", b"ab", b"abc", &[0, 0xff, b'?', b'#', b'/', b'%']];
+    for payload in payloads.iter() {
+      for base64 in [true, false].iter() {
+        let data = DataUri::new(
+          "application/octet-stream".to_string(),
+          vec![("name".to_string(), "a;b=c".to_string())],
+          payload,
+          *base64,
+        );
+        let uri = data.to_uri();
+        let parsed = Uri::parse(&uri.to_string()).unwrap();
+        assert_eq!(parsed, uri);
+        let decoded = DataUri::from_uri(&parsed).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(decoded.parameter("name"), Some("a;b=c"));
+        assert_eq!(decoded.decode().unwrap(), payload.to_vec());
+      }
+    }
+    let data = DataUri::new("text/plain".to_string(), vec![], b"Hello", true);
+    assert_eq!(data.to_string(), "data:text/plain;base64,SGVsbG8=");
+  }
+
+  #[test]
+  fn test_invalid() {
+    assert!(matches!(
+      DataUri::parse("data:text/plain"),
+      Err(SchemeError::InvalidSyntax(_))
+    ));
+    assert!(matches!(
+      DataUri::parse("http://example.com/"),
+      Err(SchemeError::UnexpectedScheme { .. })
+    ));
+  }
+}
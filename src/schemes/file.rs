@@ -0,0 +1,273 @@
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::ast::authority::Authority;
+use crate::ast::host_name::HostName;
+use crate::ast::path::Path;
+use crate::ast::scheme::{KnownScheme, Scheme};
+use crate::ast::uri::Uri;
+use crate::schemes::{expect_scheme, percent_decode, SchemeError};
+
+// segment = *pchar
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~')
+  .remove(b'!')
+  .remove(b'$')
+  .remove(b'&')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')')
+  .remove(b'*')
+  .remove(b'+')
+  .remove(b',')
+  .remove(b';')
+  .remove(b'=')
+  .remove(b':')
+  .remove(b'@');
+
+const LOCALHOST: &str = "localhost";
+
+/// Conversions between `file:` URIs (RFC 8089) and file system paths.
+impl Uri {
+  /// Converts an absolute path of the current platform to a `file:` URI.
+  pub fn from_file_path(path: &std::path::Path) -> Result<Uri, SchemeError> {
+    let path = path
+      .to_str()
+      .ok_or_else(|| SchemeError::InvalidFilePath(format!("not valid UTF-8: {:?}", path)))?;
+    if cfg!(windows) {
+      Self::from_windows_path(path)
+    } else {
+      Self::from_unix_path(path)
+    }
+  }
+
+  /// Converts a `file:` URI to a path of the current platform.
+  pub fn to_file_path(&self) -> Result<PathBuf, SchemeError> {
+    if cfg!(windows) {
+      self.to_windows_path().map(PathBuf::from)
+    } else {
+      self.to_unix_path().map(PathBuf::from)
+    }
+  }
+
+  /// Converts an absolute Unix path such as `/tmp/a b` to `file:///tmp/a%20b`.
+  pub fn from_unix_path(path: &str) -> Result<Uri, SchemeError> {
+    if !path.starts_with('/') {
+      return Err(SchemeError::InvalidFilePath(format!(
+        "not an absolute path: {:?}",
+        path
+      )));
+    }
+    Ok(file_uri("", path[1..].split('/')))
+  }
+
+  /// Converts an absolute Windows path to a `file:` URI.
+  ///
+  /// `C:\a\b` becomes `file:///C:/a/b` and the UNC path `\\server\share\a` becomes
+  /// `file://server/share/a`.
+  pub fn from_windows_path(path: &str) -> Result<Uri, SchemeError> {
+    let invalid = || SchemeError::InvalidFilePath(format!("not an absolute path: {:?}", path));
+    let (path, is_unc) = if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+      (rest, true)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+      (rest, false)
+    } else if let Some(rest) = path.strip_prefix(r"\\") {
+      (rest, true)
+    } else {
+      (path, false)
+    };
+    let mut components = path.split(&['\\', '/'][..]);
+    if is_unc {
+      let host = components
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(invalid)?;
+      Ok(file_uri(host, components))
+    } else if is_drive_letter(path) && (path.len() == 2 || path[2..].starts_with(&['\\', '/'][..]))
+    {
+      Ok(file_uri("", components))
+    } else {
+      Err(invalid())
+    }
+  }
+
+  /// Converts a `file:` URI for the local host to an absolute Unix path.
+  ///
+  /// Accepts `file:///a`, `file://localhost/a` and `file:/a`.
+  pub fn to_unix_path(&self) -> Result<String, SchemeError> {
+    let (host, segments) = self.file_components()?;
+    if !host.is_empty() && !host.eq_ignore_ascii_case(LOCALHOST) {
+      return Err(SchemeError::InvalidFilePath(format!(
+        "not a local host: {}",
+        host
+      )));
+    }
+    if !self.path().is_rooted() && self.authority().is_none() {
+      return Err(SchemeError::InvalidFilePath(format!(
+        "not an absolute path: {}",
+        self.path()
+      )));
+    }
+    Ok(format!("/{}", segments.join("/")))
+  }
+
+  /// Converts a `file:` URI to an absolute Windows path.
+  ///
+  /// Accepts `file:///C:/a`, `file:/C:/a`, `file:C:/a` and `file://server/share/a`.
+  pub fn to_windows_path(&self) -> Result<String, SchemeError> {
+    let (host, segments) = self.file_components()?;
+    if segments.iter().any(|s| s.contains('\\')) {
+      return Err(SchemeError::InvalidFilePath(format!(
+        "'\\' in a path segment: {}",
+        self.path()
+      )));
+    }
+    if !host.is_empty() && !host.eq_ignore_ascii_case(LOCALHOST) {
+      return Ok(format!(r"\\{}\{}", host, segments.join(r"\")));
+    }
+    match segments.first() {
+      Some(drive) if drive.len() == 2 && is_drive_letter(drive) => {
+        if segments.len() == 1 {
+          Ok(format!(r"{}\", drive))
+        } else {
+          Ok(segments.join(r"\"))
+        }
+      }
+      _ => Err(SchemeError::InvalidFilePath(format!(
+        "no drive letter: {}",
+        self.path()
+      ))),
+    }
+  }
+
+  fn file_components(&self) -> Result<(String, Vec<String>), SchemeError> {
+    expect_scheme(self.schema(), KnownScheme::File)?;
+    if let Some(authority) = self.authority() {
+      if authority.port().is_some() || authority.user_info().is_some() {
+        return Err(SchemeError::InvalidSyntax(format!(
+          "a file URI has neither user info nor port: {}",
+          authority
+        )));
+      }
+    }
+    let host = self
+      .authority()
+      .map(|a| a.host_name().to_string())
+      .unwrap_or("".to_string());
+    let segments = self
+      .path()
+      .parts()
+      .iter()
+      .map(|s| percent_decode(s))
+      .collect::<Result<Vec<_>, _>>()?;
+    if segments.iter().any(|s| s.contains('/') || s.contains('\0')) {
+      return Err(SchemeError::InvalidFilePath(format!(
+        "'/' or NUL in a path segment: {}",
+        self.path()
+      )));
+    }
+    Ok((host, segments))
+  }
+}
+
+fn file_uri<'a>(host: &str, segments: impl Iterator<Item = &'a str>) -> Uri {
+  let parts = segments
+    .map(|s| utf8_percent_encode(s, SEGMENT).to_string())
+    .collect_vec();
+  Uri::new(
+    Scheme::from(KnownScheme::File),
+    Some(Authority::new(HostName::from(host), None, None)),
+    Path::of_abempty_from_strings(&parts),
+    None,
+    None,
+  )
+}
+
+fn is_drive_letter(s: &str) -> bool {
+  let bytes = s.as_bytes();
+  bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{SchemeError, Uri};
+
+  #[test]
+  fn test_unix_path() {
+    let uri = Uri::from_unix_path("/home/user/a b/100%.txt").unwrap();
+    assert_eq!(uri.to_string(), "file:///home/user/a%20b/100%25.txt");
+    assert_eq!(Uri::parse(&uri.to_string()).unwrap(), uri);
+    assert_eq!(uri.to_unix_path().unwrap(), "/home/user/a b/100%.txt");
+    assert_eq!(Uri::from_unix_path("/").unwrap().to_string(), "file:///");
+    assert_eq!(
+      Uri::from_unix_path("/tmp/").unwrap().to_string(),
+      "file:///tmp/"
+    );
+    assert!(matches!(
+      Uri::from_unix_path("tmp/a"),
+      Err(SchemeError::InvalidFilePath(_))
+    ));
+
+    let path = |s: &str| Uri::parse(s).unwrap().to_unix_path();
+    assert_eq!(path("file:///etc/hosts").unwrap(), "/etc/hosts");
+    assert_eq!(path("file://localhost/etc/hosts").unwrap(), "/etc/hosts");
+    assert_eq!(path("file:/etc/hosts").unwrap(), "/etc/hosts");
+    assert_eq!(path("file:///%E3%81%82").unwrap(), "/あ");
+    assert!(path("file://server/etc/hosts").is_err());
+    assert!(path("file:etc/hosts").is_err());
+    assert!(path("file:///a%2Fb").is_err());
+    assert!(path("http://localhost/etc/hosts").is_err());
+  }
+
+  #[test]
+  fn test_windows_path() {
+    let uri = Uri::from_windows_path(r"C:\Program Files\app\a.txt").unwrap();
+    assert_eq!(uri.to_string(), "file:///C:/Program%20Files/app/a.txt");
+    assert_eq!(
+      uri.to_windows_path().unwrap(),
+      r"C:\Program Files\app\a.txt"
+    );
+    let uri = Uri::from_windows_path(r"\\server\share\dir\a.txt").unwrap();
+    assert_eq!(uri.to_string(), "file://server/share/dir/a.txt");
+    assert_eq!(uri.to_windows_path().unwrap(), r"\\server\share\dir\a.txt");
+    assert_eq!(
+      Uri::from_windows_path(r"\\?\D:\x").unwrap().to_string(),
+      "file:///D:/x"
+    );
+    assert_eq!(
+      Uri::from_windows_path(r"\\?\UNC\server\share")
+        .unwrap()
+        .to_string(),
+      "file://server/share"
+    );
+    assert_eq!(
+      Uri::from_windows_path("C:").unwrap().to_string(),
+      "file:///C:"
+    );
+    assert!(Uri::from_windows_path(r"relative\path").is_err());
+    assert!(Uri::from_windows_path(r"C:relative").is_err());
+
+    let path = |s: &str| Uri::parse(s).unwrap().to_windows_path();
+    assert_eq!(path("file:///C:/x/y").unwrap(), r"C:\x\y");
+    assert_eq!(path("file:/C:/x/y").unwrap(), r"C:\x\y");
+    assert_eq!(path("file:C:/x/y").unwrap(), r"C:\x\y");
+    assert_eq!(path("file://localhost/c:/x").unwrap(), r"c:\x");
+    assert_eq!(path("file:///C:").unwrap(), r"C:\");
+    assert_eq!(path("file:///C:/").unwrap(), r"C:\");
+    assert!(path("file:///x/y").is_err());
+    assert!(path("file:///C:/a%5Cb").is_err());
+  }
+
+  #[test]
+  fn test_file_path() {
+    let path = std::env::temp_dir().join("uri rs");
+    let uri = Uri::from_file_path(&path).unwrap();
+    assert_eq!(uri.to_file_path().unwrap(), path);
+    assert!(Uri::from_file_path(std::path::Path::new("relative")).is_err());
+  }
+}
@@ -0,0 +1,387 @@
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::ast::scheme::KnownScheme;
+use crate::ast::uri::Uri;
+use crate::schemes::{percent_decode, SchemeError};
+
+// The path of an scp-like remote is taken verbatim, so everything but pchar and "/" is encoded.
+const PATH: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~')
+  .remove(b'!')
+  .remove(b'$')
+  .remove(b'&')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')')
+  .remove(b'*')
+  .remove(b'+')
+  .remove(b',')
+  .remove(b';')
+  .remove(b'=')
+  .remove(b':')
+  .remove(b'@')
+  .remove(b'/');
+
+const GIT_SUFFIX: &str = ".git";
+
+/// A Git remote, in one of the forms accepted by `git clone`:
+///
+/// - `ssh://[user@]host[:port]/path`, also spelled `git+ssh://` or `ssh+git://`
+/// - `git://host[:port]/path`
+/// - `http[s]://host[:port]/path` and `ftp[s]://host[:port]/path`
+/// - `file:///path` or an absolute local path such as `/srv/repo.git`
+/// - the scp-like `[user@]host:path`, which is not a URI
+///
+/// Every form is normalized to a `Uri`. As in Git, the path of an scp-like remote is
+/// relative to the home directory unless it starts with `/` or `~`, so
+/// `git@github.com:org/repo.git` becomes `ssh://git@github.com/~/org/repo.git` and
+/// `git@host:/srv/repo.git` becomes `ssh://git@host/srv/repo.git`. Relative local paths are
+/// rejected, since they have no URI form without a base.
+///
+/// ```rust
+/// use uri_rs::GitRemote;
+/// let remote = GitRemote::parse("git@github.com:org/repo.git").unwrap();
+/// assert!(remote.is_scp_like());
+/// assert_eq!(remote.uri().to_string(), "ssh://git@github.com/~/org/repo.git");
+/// assert_eq!(remote.owner(), Some("org".to_string()));
+/// assert_eq!(remote.repo(), Some("repo".to_string()));
+/// assert!(remote.has_git_suffix());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitRemote {
+  uri: Uri,
+  scp_like: bool,
+}
+
+impl std::fmt::Display for GitRemote {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self.to_scp_like() {
+      Some(s) if self.scp_like => write!(f, "{}", s),
+      _ => write!(f, "{}", self.uri),
+    }
+  }
+}
+
+impl GitRemote {
+  pub fn parse(text: &str) -> Result<GitRemote, SchemeError> {
+    if text.contains("://") {
+      let uri = Uri::parse(text).map_err(|e| SchemeError::InvalidSyntax(format!("{:?}", e)))?;
+      return Self::from_uri(&uri);
+    }
+    if text.starts_with('/') {
+      return Self::from_uri(&Uri::from_unix_path(text)?);
+    }
+    if is_windows_path(text) {
+      return Self::from_uri(&Uri::from_windows_path(text)?);
+    }
+    match split_scp_like(text) {
+      Some((user_host, path)) if !path.is_empty() => {
+        let prefix = if path.starts_with('/') {
+          ""
+        } else if path.starts_with('~') {
+          "/"
+        } else {
+          "/~/"
+        };
+        let path = utf8_percent_encode(path, PATH);
+        let uri = Uri::parse(&format!("ssh://{}{}{}", user_host, prefix, path))
+          .map_err(|e| SchemeError::InvalidSyntax(format!("{:?}", e)))?;
+        let mut remote = Self::from_uri(&uri)?;
+        remote.scp_like = true;
+        Ok(remote)
+      }
+      _ => Err(SchemeError::InvalidSyntax(format!(
+        "not a Git remote: {:?}",
+        text
+      ))),
+    }
+  }
+
+  pub fn from_uri(uri: &Uri) -> Result<GitRemote, SchemeError> {
+    let scheme = uri.schema().as_lowercase();
+    let uri = match uri.schema().known() {
+      KnownScheme::Ssh
+      | KnownScheme::Git
+      | KnownScheme::Http
+      | KnownScheme::Https
+      | KnownScheme::Ftp
+      | KnownScheme::Ftps
+      | KnownScheme::File => uri.clone(),
+      _ if scheme == "git+ssh" || scheme == "ssh+git" => Uri::new(
+        KnownScheme::Ssh.into(),
+        uri.authority().cloned(),
+        uri.path().clone(),
+        uri.query().cloned(),
+        uri.fragment().cloned(),
+      ),
+      _ => {
+        return Err(SchemeError::UnexpectedScheme {
+          expected: KnownScheme::Ssh,
+          actual: uri.schema().clone(),
+        })
+      }
+    };
+    let has_host = uri
+      .authority()
+      .map(|a| !a.host_name().to_string().is_empty())
+      .unwrap_or(false);
+    if uri.schema().known() != KnownScheme::File && !has_host {
+      return Err(SchemeError::InvalidSyntax(format!(
+        "a Git remote needs a host: {}",
+        uri
+      )));
+    }
+    Ok(Self {
+      uri,
+      scp_like: false,
+    })
+  }
+
+  /// The remote as a URI; scp-like remotes are given the `ssh` scheme.
+  pub fn uri(&self) -> &Uri {
+    &self.uri
+  }
+
+  /// Whether the remote was written as `[user@]host:path`.
+  pub fn is_scp_like(&self) -> bool {
+    self.scp_like
+  }
+
+  pub fn host(&self) -> Option<String> {
+    self.uri.authority().map(|a| a.host_name().to_string())
+  }
+
+  pub fn user(&self) -> Option<&str> {
+    self
+      .uri
+      .authority()
+      .and_then(|a| a.user_info())
+      .map(|u| u.user_name())
+  }
+
+  /// Every path segment but the repository, such as `org` or the GitLab group
+  /// `group/subgroup`. A leading `~`, the home directory, is not part of the owner.
+  pub fn owner(&self) -> Option<String> {
+    let segments = self.segments();
+    if segments.len() < 2 {
+      return None;
+    }
+    segments[..segments.len() - 1]
+      .iter()
+      .map(|s| percent_decode(s).ok())
+      .collect::<Option<Vec<_>>>()
+      .map(|v| v.join("/"))
+  }
+
+  /// The last path segment without the `.git` suffix.
+  pub fn repo(&self) -> Option<String> {
+    let last = self.segments().pop()?;
+    let last = percent_decode(last).ok()?;
+    match last.strip_suffix(GIT_SUFFIX) {
+      Some(repo) if !repo.is_empty() => Some(repo.to_string()),
+      _ => Some(last),
+    }
+  }
+
+  /// Whether the last path segment ends with `.git`.
+  pub fn has_git_suffix(&self) -> bool {
+    self
+      .segments()
+      .pop()
+      .map(|s| s.len() > GIT_SUFFIX.len() && s.ends_with(GIT_SUFFIX))
+      .unwrap_or(false)
+  }
+
+  /// The scp-like spelling of an `ssh` remote without a port: `ssh://host/~/path` becomes
+  /// `host:path`, `ssh://host/~user/path` becomes `host:~user/path` and `ssh://host/path`
+  /// stays absolute as `host:/path`.
+  pub fn to_scp_like(&self) -> Option<String> {
+    let authority = self.uri.authority()?;
+    if self.uri.schema().known() != KnownScheme::Ssh || authority.port().is_some() {
+      return None;
+    }
+    let path = percent_decode(&self.uri.path().to_string()).ok()?;
+    Some(format!(
+      "{}{}:{}",
+      self
+        .user()
+        .map(|u| format!("{}@", u))
+        .unwrap_or("".to_string()),
+      authority.host_name(),
+      path
+        .strip_prefix("/~/")
+        .or_else(|| path.strip_prefix("/~").map(|_| &path[1..]))
+        .unwrap_or(&path)
+    ))
+  }
+
+  fn segments(&self) -> Vec<&String> {
+    self
+      .uri
+      .path()
+      .parts()
+      .iter()
+      .filter(|s| !s.is_empty())
+      .enumerate()
+      .filter(|(idx, s)| *idx != 0 || s.as_str() != "~")
+      .map(|(_, s)| s)
+      .collect_vec()
+  }
+}
+
+// Splits `[user@]host:path` at the colon. As in Git, a "/" before the colon makes the
+// text a local path; an IPv6 literal host is written in brackets.
+fn split_scp_like(text: &str) -> Option<(&str, &str)> {
+  let host_end = text.find(&[':', '['][..])?;
+  let host_start = text[..host_end].rfind('@').map(|idx| idx + 1).unwrap_or(0);
+  let colon = if text[host_start..].starts_with('[') {
+    host_start + text[host_start..].find("]:")? + 1
+  } else {
+    host_start + text[host_start..].find(':')?
+  };
+  if text[..colon].contains('/') || colon == host_start {
+    return None;
+  }
+  Some((&text[..colon], &text[colon + 1..]))
+}
+
+fn is_windows_path(text: &str) -> bool {
+  let bytes = text.as_bytes();
+  bytes.len() >= 3
+    && bytes[0].is_ascii_alphabetic()
+    && bytes[1] == b':'
+    && (bytes[2] == b'\\' || bytes[2] == b'/')
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{GitRemote, SchemeError};
+
+  #[test]
+  fn test_scp_like() {
+    let remote = GitRemote::parse("git@github.com:org/repo.git").unwrap();
+    assert!(remote.is_scp_like());
+    assert_eq!(remote.host(), Some("github.com".to_string()));
+    assert_eq!(remote.user(), Some("git"));
+    assert_eq!(remote.to_string(), "git@github.com:org/repo.git");
+
+    let remote = GitRemote::parse("example.com:group/sub group/repo").unwrap();
+    assert_eq!(
+      remote.uri().to_string(),
+      "ssh://example.com/~/group/sub%20group/repo"
+    );
+    assert_eq!(remote.owner(), Some("group/sub group".to_string()));
+    assert_eq!(remote.repo(), Some("repo".to_string()));
+    assert!(!remote.has_git_suffix());
+    assert_eq!(remote.to_string(), "example.com:group/sub group/repo");
+
+    let remote = GitRemote::parse("deploy@host:repos/a@b.git").unwrap();
+    assert_eq!(
+      remote.uri().to_string(),
+      "ssh://deploy@host/~/repos/a@b.git"
+    );
+
+    let remote = GitRemote::parse("git@[::1]:~/repo.git").unwrap();
+    assert_eq!(remote.uri().to_string(), "ssh://git@[::1]/~/repo.git");
+    assert_eq!(remote.owner(), None);
+    assert_eq!(remote.to_string(), "git@[::1]:repo.git");
+  }
+
+  #[test]
+  fn test_scp_like_home_and_absolute_paths() {
+    let relative = GitRemote::parse("git@host:srv/repo.git").unwrap();
+    let absolute = GitRemote::parse("git@host:/srv/repo.git").unwrap();
+    let other_home = GitRemote::parse("git@host:~alice/repo.git").unwrap();
+    assert_eq!(relative.uri().to_string(), "ssh://git@host/~/srv/repo.git");
+    assert_eq!(absolute.uri().to_string(), "ssh://git@host/srv/repo.git");
+    assert_eq!(
+      other_home.uri().to_string(),
+      "ssh://git@host/~alice/repo.git"
+    );
+    assert_ne!(relative.uri(), absolute.uri());
+    for (remote, text) in [
+      (&relative, "git@host:srv/repo.git"),
+      (&absolute, "git@host:/srv/repo.git"),
+      (&other_home, "git@host:~alice/repo.git"),
+    ]
+    .iter()
+    {
+      assert_eq!(remote.to_scp_like(), Some(text.to_string()));
+      assert_eq!(remote.to_string(), *text);
+      let reparsed = GitRemote::parse(&remote.to_string()).unwrap();
+      assert_eq!(reparsed.uri(), remote.uri());
+    }
+    let url = GitRemote::parse("ssh://git@host/~/srv/repo.git").unwrap();
+    assert_eq!(url.to_scp_like(), Some("git@host:srv/repo.git".to_string()));
+    assert_eq!(relative.owner(), Some("srv".to_string()));
+    assert_eq!(absolute.owner(), Some("srv".to_string()));
+  }
+
+  #[test]
+  fn test_url() {
+    let remote = GitRemote::parse("ssh://git@host:2222/org/repo.git").unwrap();
+    assert!(!remote.is_scp_like());
+    assert_eq!(remote.uri().authority().unwrap().port(), Some(2222));
+    assert_eq!(remote.owner(), Some("org".to_string()));
+    assert_eq!(remote.repo(), Some("repo".to_string()));
+    assert_eq!(remote.to_scp_like(), None);
+
+    let remote = GitRemote::parse("git+ssh://git@host/org/repo.git").unwrap();
+    assert_eq!(remote.uri().to_string(), "ssh://git@host/org/repo.git");
+    assert_eq!(
+      remote.to_scp_like(),
+      Some("git@host:/org/repo.git".to_string())
+    );
+
+    let remote = GitRemote::parse("https://github.com/org/repo.git/").unwrap();
+    assert_eq!(remote.owner(), Some("org".to_string()));
+    assert_eq!(remote.repo(), Some("repo".to_string()));
+    assert!(remote.has_git_suffix());
+
+    let remote = GitRemote::parse("git://host/repo.git").unwrap();
+    assert_eq!(remote.owner(), None);
+    assert_eq!(remote.repo(), Some("repo".to_string()));
+  }
+
+  #[test]
+  fn test_local() {
+    let remote = GitRemote::parse("/srv/git/project.git").unwrap();
+    assert_eq!(remote.uri().to_string(), "file:///srv/git/project.git");
+    assert_eq!(remote.repo(), Some("project".to_string()));
+    let remote = GitRemote::parse(r"C:\repos\project").unwrap();
+    assert_eq!(remote.uri().to_string(), "file:///C:/repos/project");
+    let remote = GitRemote::parse("file:///srv/git/project.git").unwrap();
+    assert_eq!(remote.owner(), Some("srv/git".to_string()));
+  }
+
+  #[test]
+  fn test_empty_user() {
+    let remote = GitRemote::parse("@host:repo").unwrap();
+    assert!(remote.is_scp_like());
+    assert_eq!(remote.user(), Some(""));
+    assert_eq!(remote.host(), Some("host".to_string()));
+    assert_eq!(remote.uri().to_string(), "ssh://@host/~/repo");
+    assert_eq!(remote.to_string(), "@host:repo");
+  }
+
+  #[test]
+  fn test_invalid() {
+    for s in ["repo", "./repo:x", "host:", ":repo", "http:///repo"].iter() {
+      assert!(
+        matches!(GitRemote::parse(s), Err(SchemeError::InvalidSyntax(_))),
+        "{}",
+        s
+      );
+    }
+    assert!(matches!(
+      GitRemote::parse("mailto://a@b/c"),
+      Err(SchemeError::UnexpectedScheme { .. })
+    ));
+  }
+}
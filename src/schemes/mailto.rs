@@ -0,0 +1,293 @@
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::ast::path::Path;
+use crate::ast::query::Query;
+use crate::ast::scheme::{KnownScheme, Scheme};
+use crate::ast::uri::Uri;
+use crate::schemes::{expect_scheme, percent_decode, SchemeError};
+
+// qchar = unreserved / pct-encoded / some-delims
+const QCHAR: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~')
+  .remove(b'!')
+  .remove(b'$')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')')
+  .remove(b'*')
+  .remove(b'+')
+  .remove(b',')
+  .remove(b';')
+  .remove(b':')
+  .remove(b'@');
+
+// "," separates addresses in the "to" part, so it is encoded inside an address.
+const ADDR: &AsciiSet = &QCHAR.add(b',');
+
+/// A header field name of a `mailto:` URI.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MailtoHeader {
+  To,
+  Cc,
+  Bcc,
+  Subject,
+  Body,
+  InReplyTo,
+  Other(String),
+}
+
+impl From<&str> for MailtoHeader {
+  fn from(src: &str) -> Self {
+    match src.to_ascii_lowercase().as_str() {
+      "to" => MailtoHeader::To,
+      "cc" => MailtoHeader::Cc,
+      "bcc" => MailtoHeader::Bcc,
+      "subject" => MailtoHeader::Subject,
+      "body" => MailtoHeader::Body,
+      "in-reply-to" => MailtoHeader::InReplyTo,
+      other => MailtoHeader::Other(other.to_string()),
+    }
+  }
+}
+
+impl MailtoHeader {
+  pub fn as_str(&self) -> &str {
+    match self {
+      MailtoHeader::To => "to",
+      MailtoHeader::Cc => "cc",
+      MailtoHeader::Bcc => "bcc",
+      MailtoHeader::Subject => "subject",
+      MailtoHeader::Body => "body",
+      MailtoHeader::InReplyTo => "in-reply-to",
+      MailtoHeader::Other(name) => name,
+    }
+  }
+}
+
+/// A `mailto:` URI (RFC 6068) with decoded recipients and header fields.
+///
+/// ```rust
+/// use uri_rs::{MailtoHeader, MailtoUri, Uri};
+/// let uri = Uri::parse("mailto:a@x.com,b@y.com?subject=Hi%20there&cc=c@z.com").unwrap();
+/// let mailto = MailtoUri::from_uri(&uri).unwrap();
+/// assert_eq!(mailto.to(), &vec!["a@x.com".to_string(), "b@y.com".to_string()]);
+/// assert_eq!(mailto.subject().unwrap(), "Hi there");
+///
+/// let mut mailto = MailtoUri::new(vec!["a@x.com".to_string()]);
+/// mailto.add_header(MailtoHeader::Body, "line 1\r\nline 2".to_string());
+/// assert_eq!(mailto.to_string(), "mailto:a@x.com?body=line%201%0D%0Aline%202");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailtoUri {
+  to: Vec<String>,
+  headers: Vec<(MailtoHeader, String)>,
+}
+
+impl Default for MailtoUri {
+  fn default() -> Self {
+    MailtoUri {
+      to: Vec::default(),
+      headers: Vec::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for MailtoUri {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.to_uri())
+  }
+}
+
+impl MailtoUri {
+  pub fn new(to: Vec<String>) -> Self {
+    Self {
+      to,
+      headers: Vec::new(),
+    }
+  }
+
+  pub fn parse(text: &str) -> Result<MailtoUri, SchemeError> {
+    let uri = Uri::parse(text).map_err(|e| SchemeError::InvalidSyntax(format!("{:?}", e)))?;
+    Self::from_uri(&uri)
+  }
+
+  pub fn from_uri(uri: &Uri) -> Result<MailtoUri, SchemeError> {
+    expect_scheme(uri.schema(), KnownScheme::Mailto)?;
+    if uri.authority().is_some() {
+      return Err(SchemeError::InvalidSyntax(
+        "a mailto URI has no authority".to_string(),
+      ));
+    }
+    let to = uri
+      .path()
+      .to_string()
+      .split(',')
+      .filter(|s| !s.is_empty())
+      .map(percent_decode)
+      .collect::<Result<Vec<_>, _>>()?;
+    let mut headers = Vec::new();
+    for (name, value) in uri.query().iter().flat_map(|q| q.key_values().iter()) {
+      let name = percent_decode(name)?;
+      let value = percent_decode(value.as_deref().unwrap_or(""))?;
+      headers.push((MailtoHeader::from(name.as_str()), value));
+    }
+    Ok(Self { to, headers })
+  }
+
+  /// Addresses in the "to" part, before the `?`.
+  pub fn to(&self) -> &Vec<String> {
+    &self.to
+  }
+
+  /// Addresses in the "to" part followed by those in `to` header fields.
+  pub fn recipients(&self) -> Vec<&str> {
+    self
+      .to
+      .iter()
+      .map(|s| s.as_str())
+      .chain(self.header_addresses(&MailtoHeader::To))
+      .collect_vec()
+  }
+
+  pub fn headers(&self) -> &Vec<(MailtoHeader, String)> {
+    &self.headers
+  }
+
+  pub fn header(&self, name: &MailtoHeader) -> Vec<&String> {
+    self
+      .headers
+      .iter()
+      .filter(|(n, _)| n == name)
+      .map(|(_, v)| v)
+      .collect_vec()
+  }
+
+  pub fn subject(&self) -> Option<&String> {
+    self.header(&MailtoHeader::Subject).into_iter().next()
+  }
+
+  pub fn body(&self) -> Option<&String> {
+    self.header(&MailtoHeader::Body).into_iter().next()
+  }
+
+  pub fn cc(&self) -> Vec<&str> {
+    self.header_addresses(&MailtoHeader::Cc).collect_vec()
+  }
+
+  pub fn bcc(&self) -> Vec<&str> {
+    self.header_addresses(&MailtoHeader::Bcc).collect_vec()
+  }
+
+  fn header_addresses<'a>(&'a self, name: &'a MailtoHeader) -> impl Iterator<Item = &'a str> {
+    self
+      .headers
+      .iter()
+      .filter(move |(n, _)| n == name)
+      .flat_map(|(_, v)| v.split(','))
+      .map(|s| s.trim())
+      .filter(|s| !s.is_empty())
+  }
+
+  pub fn add_to(&mut self, address: String) {
+    self.to.push(address);
+  }
+
+  pub fn add_header(&mut self, name: MailtoHeader, value: String) {
+    self.headers.push((name, value));
+  }
+
+  pub fn to_uri(&self) -> Uri {
+    let to = self
+      .to
+      .iter()
+      .map(|s| utf8_percent_encode(s, ADDR).to_string())
+      .join(",");
+    let path = if to.is_empty() {
+      Path::of_empty()
+    } else {
+      Path::of_rootless_from_strings(&[to])
+    };
+    let query = if self.headers.is_empty() {
+      None
+    } else {
+      Some(Query::new(
+        self
+          .headers
+          .iter()
+          .map(|(name, value)| {
+            (
+              utf8_percent_encode(name.as_str(), QCHAR).to_string(),
+              Some(utf8_percent_encode(value, QCHAR).to_string()),
+            )
+          })
+          .collect_vec(),
+      ))
+    };
+    Uri::new(Scheme::from(KnownScheme::Mailto), None, path, query, None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{MailtoHeader, MailtoUri, SchemeError, Uri};
+
+  #[test]
+  fn test_from_uri() {
+    let uri = Uri::parse(
+      "mailto:a@x.com,b@y.com?subject=Hi&cc=c@z.com,d@z.com&body=Hello%20World%21&X-Mailer=uri",
+    )
+    .unwrap();
+    let mailto = MailtoUri::from_uri(&uri).unwrap();
+    assert_eq!(
+      mailto.to(),
+      &vec!["a@x.com".to_string(), "b@y.com".to_string()]
+    );
+    assert_eq!(mailto.subject().unwrap(), "Hi");
+    assert_eq!(mailto.cc(), vec!["c@z.com", "d@z.com"]);
+    assert!(mailto.bcc().is_empty());
+    assert_eq!(mailto.body().unwrap(), "Hello World!");
+    assert_eq!(
+      mailto.header(&MailtoHeader::Other("x-mailer".to_string())),
+      vec!["uri"]
+    );
+  }
+
+  #[test]
+  fn test_recipients() {
+    let mailto = MailtoUri::parse("mailto:?to=a@x.com,b@y.com&subject=s").unwrap();
+    assert!(mailto.to().is_empty());
+    assert_eq!(mailto.recipients(), vec!["a@x.com", "b@y.com"]);
+    let mailto = MailtoUri::parse("mailto:%22not%40me%22@example.org?to=c@x.com").unwrap();
+    assert_eq!(
+      mailto.recipients(),
+      vec!["\"not@me\"@example.org", "c@x.com"]
+    );
+  }
+
+  #[test]
+  fn test_to_uri() {
+    let mut mailto = MailtoUri::new(vec!["a,b@x.com".to_string()]);
+    mailto.add_to("user@例え.jp".to_string());
+    mailto.add_header(MailtoHeader::Subject, "Hi & bye = 100%".to_string());
+    mailto.add_header(MailtoHeader::Cc, "c@z.com".to_string());
+    let uri = mailto.to_uri();
+    assert_eq!(
+      uri.to_string(),
+      "mailto:a%2Cb@x.com,user@%E4%BE%8B%E3%81%88.jp?subject=Hi%20%26%20bye%20%3D%20100%25&cc=c@z.com"
+    );
+    assert_eq!(Uri::parse(&uri.to_string()).unwrap(), uri);
+    assert_eq!(MailtoUri::from_uri(&uri).unwrap(), mailto);
+  }
+
+  #[test]
+  fn test_unexpected_scheme() {
+    let result = MailtoUri::parse("http://example.com/");
+    assert!(matches!(result, Err(SchemeError::UnexpectedScheme { .. })));
+  }
+}
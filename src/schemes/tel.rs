@@ -0,0 +1,473 @@
+use std::fmt::Formatter;
+
+use itertools::Itertools;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::ast::path::Path;
+use crate::ast::scheme::{KnownScheme, Scheme};
+use crate::ast::uri::Uri;
+use crate::schemes::{expect_scheme, percent_decode, SchemeError};
+
+// paramchar = param-unreserved / unreserved / pct-encoded
+const PARAM: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'_')
+  .remove(b'~')
+  .remove(b'[')
+  .remove(b']')
+  .remove(b'/')
+  .remove(b':')
+  .remove(b'&')
+  .remove(b'+')
+  .remove(b'$');
+
+// uric = reserved / unreserved / pct-encoded, minus what ends the parameter or the URI
+const URIC: &AsciiSet = &PARAM
+  .remove(b'!')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')')
+  .remove(b'*')
+  .remove(b',')
+  .remove(b'=')
+  .remove(b'@');
+
+// local-number-digits may contain "*" and "#", and "#" would start a fragment.
+const NUMBER: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'+')
+  .remove(b'*')
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'(')
+  .remove(b')');
+
+const EXT: &str = "ext";
+const ISUB: &str = "isub";
+const PHONE_CONTEXT: &str = "phone-context";
+
+/// A `tel:` URI (RFC 3966).
+///
+/// The number is either global (`+1-201-555-0123`) or local, in which case a
+/// `phone-context` is required. Parameter values are kept decoded.
+///
+/// ```rust
+/// use uri_rs::{TelUri, Uri};
+/// let uri = Uri::parse("tel:+1-201-555-0123;ext=1234").unwrap();
+/// let tel = TelUri::from_uri(&uri).unwrap();
+/// assert!(tel.is_global());
+/// assert_eq!(tel.extension(), Some("1234"));
+/// assert_eq!(tel.normalized().to_string(), "tel:+12015550123;ext=1234");
+///
+/// let tel = TelUri::parse("tel:7042;phone-context=example.com").unwrap();
+/// assert_eq!(tel.phone_context(), Some("example.com"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelUri {
+  number: String,
+  extension: Option<String>,
+  isdn_subaddress: Option<String>,
+  phone_context: Option<String>,
+  parameters: Vec<(String, Option<String>)>,
+}
+
+impl Default for TelUri {
+  fn default() -> Self {
+    TelUri {
+      number: String::default(),
+      extension: Option::default(),
+      isdn_subaddress: Option::default(),
+      phone_context: Option::default(),
+      parameters: Vec::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for TelUri {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.to_uri())
+  }
+}
+
+impl TelUri {
+  /// A global number such as `+1-201-555-0123`.
+  pub fn global(number: &str) -> Result<TelUri, SchemeError> {
+    Self::new(number.to_string(), None)
+  }
+
+  /// A local number such as `7042` valid within `phone_context`, which is a domain
+  /// name or a global number prefix.
+  pub fn local(number: &str, phone_context: &str) -> Result<TelUri, SchemeError> {
+    Self::new(number.to_string(), Some(phone_context.to_string()))
+  }
+
+  fn new(number: String, phone_context: Option<String>) -> Result<TelUri, SchemeError> {
+    let tel = Self {
+      number,
+      phone_context,
+      ..Self::default()
+    };
+    tel.validate()?;
+    Ok(tel)
+  }
+
+  pub fn parse(text: &str) -> Result<TelUri, SchemeError> {
+    let uri = Uri::parse(text).map_err(|e| SchemeError::InvalidSyntax(format!("{:?}", e)))?;
+    Self::from_uri(&uri)
+  }
+
+  pub fn from_uri(uri: &Uri) -> Result<TelUri, SchemeError> {
+    expect_scheme(uri.schema(), KnownScheme::Tel)?;
+    if uri.authority().is_some() || uri.query().is_some() || uri.fragment().is_some() {
+      return Err(SchemeError::InvalidSyntax(format!(
+        "a tel URI has only a telephone-subscriber part: {}",
+        uri
+      )));
+    }
+    let text = uri.path().to_string();
+    let mut items = text.split(';');
+    let mut tel = Self {
+      number: percent_decode(items.next().unwrap_or(""))?,
+      ..Self::default()
+    };
+    for item in items {
+      let (name, value) = match item.find('=') {
+        Some(idx) => (&item[..idx], Some(percent_decode(&item[idx + 1..])?)),
+        None => (item, None),
+      };
+      let field = match name.to_ascii_lowercase().as_str() {
+        EXT => &mut tel.extension,
+        ISUB => &mut tel.isdn_subaddress,
+        PHONE_CONTEXT => &mut tel.phone_context,
+        _ => {
+          tel.parameters.push((name.to_string(), value));
+          continue;
+        }
+      };
+      if field.is_some() {
+        return Err(SchemeError::InvalidSyntax(format!(
+          "duplicate parameter {:?} in {}",
+          name, uri
+        )));
+      }
+      *field = Some(value.ok_or_else(|| {
+        SchemeError::InvalidSyntax(format!("parameter {:?} has no value in {}", name, uri))
+      })?);
+    }
+    tel.validate()?;
+    Ok(tel)
+  }
+
+  fn validate(&self) -> Result<(), SchemeError> {
+    let invalid = |what: &str, value: &str| {
+      Err(SchemeError::InvalidSyntax(format!(
+        "invalid {}: {:?}",
+        what, value
+      )))
+    };
+    if self.is_global() {
+      if !is_global_number_digits(&self.number) {
+        return invalid("global number", &self.number);
+      }
+      if let Some(context) = &self.phone_context {
+        return invalid("phone-context for a global number", context);
+      }
+    } else {
+      if !is_local_number_digits(&self.number) {
+        return invalid("local number", &self.number);
+      }
+      match &self.phone_context {
+        None => return invalid("local number without phone-context", &self.number),
+        Some(context) if !is_global_number_digits(context) && !is_domain_name(context) => {
+          return invalid(PHONE_CONTEXT, context)
+        }
+        _ => {}
+      }
+    }
+    if let Some(extension) = &self.extension {
+      if extension.is_empty() || !extension.chars().all(is_phonedigit) {
+        return invalid(EXT, extension);
+      }
+    }
+    if let Some(isdn_subaddress) = &self.isdn_subaddress {
+      if isdn_subaddress.is_empty() {
+        return invalid(ISUB, isdn_subaddress);
+      }
+    }
+    for (name, value) in &self.parameters {
+      if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return invalid("parameter name", name);
+      }
+      if value.as_ref().map(|v| v.is_empty()).unwrap_or(false) {
+        return invalid("parameter value", name);
+      }
+    }
+    Ok(())
+  }
+
+  /// The number as written, including the leading `+` of a global number.
+  pub fn number(&self) -> &str {
+    &self.number
+  }
+
+  pub fn is_global(&self) -> bool {
+    self.number.starts_with('+')
+  }
+
+  pub fn phone_context(&self) -> Option<&str> {
+    self.phone_context.as_deref()
+  }
+
+  pub fn extension(&self) -> Option<&str> {
+    self.extension.as_deref()
+  }
+
+  pub fn isdn_subaddress(&self) -> Option<&str> {
+    self.isdn_subaddress.as_deref()
+  }
+
+  /// Parameters other than `ext`, `isub` and `phone-context`.
+  pub fn parameters(&self) -> &Vec<(String, Option<String>)> {
+    &self.parameters
+  }
+
+  pub fn parameter(&self, name: &str) -> Option<Option<&str>> {
+    self
+      .parameters
+      .iter()
+      .find(|(n, _)| n.eq_ignore_ascii_case(name))
+      .map(|(_, v)| v.as_deref())
+  }
+
+  pub fn set_extension(&mut self, extension: &str) -> Result<(), SchemeError> {
+    let mut tel = self.clone();
+    tel.extension = Some(extension.to_string());
+    tel.validate()?;
+    *self = tel;
+    Ok(())
+  }
+
+  pub fn add_parameter(&mut self, name: &str, value: Option<&str>) -> Result<(), SchemeError> {
+    let mut tel = self.clone();
+    tel
+      .parameters
+      .push((name.to_string(), value.map(|s| s.to_string())));
+    tel.validate()?;
+    *self = tel;
+    Ok(())
+  }
+
+  /// The number without visual separators, with hex digits upper-cased.
+  pub fn normalized_number(&self) -> String {
+    remove_visual_separators(&self.number).to_ascii_uppercase()
+  }
+
+  /// The form used for comparison (RFC 3966, section 4): visual separators are removed,
+  /// domain names and parameter names are lower-cased, and the other parameters are
+  /// sorted by name.
+  pub fn normalized(&self) -> TelUri {
+    let phone_context = self.phone_context.as_ref().map(|c| {
+      if c.starts_with('+') {
+        remove_visual_separators(c)
+      } else {
+        c.to_ascii_lowercase()
+      }
+    });
+    let parameters = self
+      .parameters
+      .iter()
+      .map(|(n, v)| (n.to_ascii_lowercase(), v.clone()))
+      .sorted_by(|a, b| a.0.cmp(&b.0))
+      .collect_vec();
+    Self {
+      number: self.normalized_number(),
+      extension: self.extension.as_deref().map(remove_visual_separators),
+      isdn_subaddress: self.isdn_subaddress.clone(),
+      phone_context,
+      parameters,
+    }
+  }
+
+  /// Whether both URIs are equal after normalization.
+  pub fn is_equivalent(&self, other: &TelUri) -> bool {
+    self.normalized() == other.normalized()
+  }
+
+  pub fn to_uri(&self) -> Uri {
+    let param = |name: &str, value: Option<&str>, set: &'static AsciiSet| match value {
+      Some(value) => format!(";{}={}", name, utf8_percent_encode(value, set)),
+      None => format!(";{}", name),
+    };
+    let text = std::iter::once(utf8_percent_encode(&self.number, NUMBER).to_string())
+      .chain(
+        self
+          .extension
+          .as_deref()
+          .map(|v| param(EXT, Some(v), PARAM)),
+      )
+      .chain(
+        self
+          .isdn_subaddress
+          .as_deref()
+          .map(|v| param(ISUB, Some(v), URIC)),
+      )
+      .chain(
+        self
+          .phone_context
+          .as_deref()
+          .map(|v| param(PHONE_CONTEXT, Some(v), PARAM)),
+      )
+      .chain(
+        self
+          .parameters
+          .iter()
+          .map(|(n, v)| param(n, v.as_deref(), PARAM)),
+      )
+      .join("");
+    let parts = text.split('/').map(|s| s.to_string()).collect_vec();
+    Uri::new(
+      Scheme::from(KnownScheme::Tel),
+      None,
+      Path::of_rootless_from_strings(&parts),
+      None,
+      None,
+    )
+  }
+}
+
+// visual-separator = "-" / "." / "(" / ")"
+fn is_visual_separator(c: char) -> bool {
+  matches!(c, '-' | '.' | '(' | ')')
+}
+
+// phonedigit = DIGIT / [ visual-separator ]
+fn is_phonedigit(c: char) -> bool {
+  c.is_ascii_digit() || is_visual_separator(c)
+}
+
+fn remove_visual_separators(s: &str) -> String {
+  s.chars().filter(|c| !is_visual_separator(*c)).collect()
+}
+
+// global-number-digits = "+" *phonedigit DIGIT *phonedigit
+fn is_global_number_digits(s: &str) -> bool {
+  match s.strip_prefix('+') {
+    Some(digits) => digits.chars().all(is_phonedigit) && digits.chars().any(|c| c.is_ascii_digit()),
+    None => false,
+  }
+}
+
+// local-number-digits = *phonedigit-hex (HEXDIG / "*" / "#") *phonedigit-hex
+fn is_local_number_digits(s: &str) -> bool {
+  let is_digit = |c: char| c.is_ascii_hexdigit() || c == '*' || c == '#';
+  s.chars().all(|c| is_digit(c) || is_visual_separator(c)) && s.chars().any(is_digit)
+}
+
+// domainname = *( domainlabel "." ) toplabel [ "." ]
+fn is_domain_name(s: &str) -> bool {
+  let s = s.strip_suffix('.').unwrap_or(s);
+  let labels = s.split('.').collect_vec();
+  labels.iter().all(|label| {
+    !label.is_empty()
+      && !label.starts_with('-')
+      && !label.ends_with('-')
+      && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+  }) && labels
+    .last()
+    .and_then(|top| top.chars().next())
+    .map(|c| c.is_ascii_alphabetic())
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{SchemeError, TelUri, Uri};
+
+  #[test]
+  fn test_global_number() {
+    let tel = TelUri::parse("tel:+1-201-555-0123;ext=12.34;isub=1411%2F;X-Foo=bar;tgrp").unwrap();
+    assert!(tel.is_global());
+    assert_eq!(tel.number(), "+1-201-555-0123");
+    assert_eq!(tel.normalized_number(), "+12015550123");
+    assert_eq!(tel.extension(), Some("12.34"));
+    assert_eq!(tel.isdn_subaddress(), Some("1411/"));
+    assert_eq!(tel.phone_context(), None);
+    assert_eq!(tel.parameter("x-foo"), Some(Some("bar")));
+    assert_eq!(tel.parameter("tgrp"), Some(None));
+    assert_eq!(tel.parameter("none"), None);
+    assert_eq!(
+      tel.to_string(),
+      "tel:+1-201-555-0123;ext=12.34;isub=1411/;X-Foo=bar;tgrp"
+    );
+    let uri = tel.to_uri();
+    assert_eq!(Uri::parse(&uri.to_string()).unwrap(), uri);
+    assert_eq!(TelUri::from_uri(&uri).unwrap(), tel);
+  }
+
+  #[test]
+  fn test_local_number() {
+    let tel = TelUri::parse("tel:7042;phone-context=Example.COM").unwrap();
+    assert!(!tel.is_global());
+    assert_eq!(tel.phone_context(), Some("Example.COM"));
+    let tel = TelUri::parse("tel:*1%23;phone-context=+1-201").unwrap();
+    assert_eq!(tel.number(), "*1#");
+    assert_eq!(tel.to_string(), "tel:*1%23;phone-context=+1-201");
+    let tel = TelUri::local("a1-b", "+44").unwrap();
+    assert_eq!(tel.normalized_number(), "A1B");
+  }
+
+  #[test]
+  fn test_normalized() {
+    let a = TelUri::parse("tel:+1-(201)-555.0123;b=2;A=1").unwrap();
+    let b = TelUri::parse("tel:+12015550123;a=1;b=2").unwrap();
+    assert_ne!(a, b);
+    assert!(a.is_equivalent(&b));
+    assert_eq!(a.normalized().to_string(), "tel:+12015550123;a=1;b=2");
+    let a = TelUri::parse("tel:863-1234;phone-context=+1-914-555").unwrap();
+    assert_eq!(
+      a.normalized().to_string(),
+      "tel:8631234;phone-context=+1914555"
+    );
+  }
+
+  #[test]
+  fn test_builder() {
+    let mut tel = TelUri::global("+81-3-1234-5678").unwrap();
+    tel.set_extension("99").unwrap();
+    tel.add_parameter("isdn", None).unwrap();
+    assert_eq!(tel.to_string(), "tel:+81-3-1234-5678;ext=99;isdn");
+    assert!(tel.set_extension("x").is_err());
+    assert!(tel.add_parameter("bad name", None).is_err());
+    assert_eq!(tel.to_string(), "tel:+81-3-1234-5678;ext=99;isdn");
+  }
+
+  #[test]
+  fn test_invalid() {
+    for s in [
+      "tel:",
+      "tel:+",
+      "tel:+1-abc",
+      "tel:7042",
+      "tel:7042;phone-context=-bad-.com",
+      "tel:7042;phone-context=example.123",
+      "tel:+1-201;phone-context=example.com",
+      "tel:+1-201;ext=",
+      "tel:+1-201;ext=1a",
+      "tel:+1-201;ext=1;ext=2",
+      "tel:+1-201?x=1",
+      "tel://+1-201",
+    ]
+    .iter()
+    {
+      assert!(
+        matches!(TelUri::parse(s), Err(SchemeError::InvalidSyntax(_))),
+        "{}",
+        s
+      );
+    }
+    assert!(matches!(
+      TelUri::parse("sip:+1-201@example.com"),
+      Err(SchemeError::UnexpectedScheme { .. })
+    ));
+  }
+}
@@ -0,0 +1,299 @@
+use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+
+use itertools::Itertools;
+
+use crate::ast::scheme::KnownScheme;
+use crate::ast::uri::Uri;
+use crate::parser::parsers::{Elms, urn_parsers};
+use crate::schemes::{expect_scheme, SchemeError};
+
+/// A URN (RFC 8141), such as `urn:isbn:0451450523`.
+///
+/// Equality and hashing follow URN-equivalence: the NID is compared case-insensitively,
+/// percent-encodings are compared after upper-casing their hex digits, and the r-, q- and
+/// f-components are ignored.
+///
+/// ```rust
+/// use uri_rs::Urn;
+/// let urn = Urn::parse("urn:example:a%2cb?+CCResolve:cc=uk?=op=map#top").unwrap();
+/// assert_eq!(urn.nid(), "example");
+/// assert_eq!(urn.nss(), "a%2cb");
+/// assert_eq!(urn.r_component(), Some(&"CCResolve:cc=uk".to_string()));
+/// assert_eq!(urn.q_component(), Some(&"op=map".to_string()));
+/// assert_eq!(urn.f_component(), Some(&"top".to_string()));
+/// assert_eq!(urn, Urn::parse("URN:EXAMPLE:a%2Cb").unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Urn {
+  nid: String,
+  nss: String,
+  r_component: Option<String>,
+  q_component: Option<String>,
+  f_component: Option<String>,
+}
+
+impl PartialEq for Urn {
+  fn eq(&self, other: &Self) -> bool {
+    self.nid.eq_ignore_ascii_case(&other.nid)
+      && normalize_pct_encoded(&self.nss) == normalize_pct_encoded(&other.nss)
+  }
+}
+
+impl Eq for Urn {}
+
+impl Hash for Urn {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.nid.to_ascii_lowercase().hash(state);
+    normalize_pct_encoded(&self.nss).hash(state);
+  }
+}
+
+impl std::fmt::Display for Urn {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "urn:{}:{}{}{}{}",
+      self.nid,
+      self.nss,
+      self
+        .r_component
+        .as_ref()
+        .map(|s| format!("?+{}", s))
+        .unwrap_or("".to_string()),
+      self
+        .q_component
+        .as_ref()
+        .map(|s| format!("?={}", s))
+        .unwrap_or("".to_string()),
+      self
+        .f_component
+        .as_ref()
+        .map(|s| format!("#{}", s))
+        .unwrap_or("".to_string())
+    )
+  }
+}
+
+impl Urn {
+  pub fn new(
+    nid: String,
+    nss: String,
+    r_component: Option<String>,
+    q_component: Option<String>,
+    f_component: Option<String>,
+  ) -> Self {
+    Self {
+      nid,
+      nss,
+      r_component,
+      q_component,
+      f_component,
+    }
+  }
+
+  pub fn parse(text: &str) -> Result<Urn, SchemeError> {
+    urn_parsers::urn(Elms::new(text.as_bytes()))
+      .map(|(_, v)| v)
+      .map_err(|e| SchemeError::InvalidSyntax(format!("{:?}", e)))
+  }
+
+  pub fn from_uri(uri: &Uri) -> Result<Urn, SchemeError> {
+    expect_scheme(uri.schema(), KnownScheme::Urn)?;
+    Self::parse(&uri.to_string())
+  }
+
+  /// `urn:uuid:` followed by the canonical lower-case form of `uuid` (RFC 4122).
+  pub fn from_uuid(uuid: u128) -> Self {
+    let hex = format!("{:032x}", uuid);
+    let nss = format!(
+      "{}-{}-{}-{}-{}",
+      &hex[0..8],
+      &hex[8..12],
+      &hex[12..16],
+      &hex[16..20],
+      &hex[20..32]
+    );
+    Self::new("uuid".to_string(), nss, None, None, None)
+  }
+
+  /// Namespace identifier.
+  pub fn nid(&self) -> &str {
+    &self.nid
+  }
+
+  /// Namespace-specific string.
+  pub fn nss(&self) -> &str {
+    &self.nss
+  }
+
+  pub fn r_component(&self) -> Option<&String> {
+    self.r_component.as_ref()
+  }
+
+  pub fn q_component(&self) -> Option<&String> {
+    self.q_component.as_ref()
+  }
+
+  pub fn f_component(&self) -> Option<&String> {
+    self.f_component.as_ref()
+  }
+
+  /// The assigned name in its URN-equivalence normal form, without r-, q- and f-components.
+  pub fn normalized(&self) -> Urn {
+    Self::new(
+      self.nid.to_ascii_lowercase(),
+      normalize_pct_encoded(&self.nss),
+      None,
+      None,
+      None,
+    )
+  }
+
+  pub fn is_nid(&self, nid: &str) -> bool {
+    self.nid.eq_ignore_ascii_case(nid)
+  }
+
+  /// The UUID of a `urn:uuid:` URN.
+  pub fn uuid(&self) -> Option<u128> {
+    if !self.is_nid("uuid") {
+      return None;
+    }
+    let groups = self.nss.split('-').collect_vec();
+    let lengths = groups.iter().map(|g| g.len()).collect_vec();
+    if lengths != [8, 4, 4, 4, 12]
+      || !groups
+        .iter()
+        .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+      return None;
+    }
+    u128::from_str_radix(&groups.concat(), 16).ok()
+  }
+
+  /// The ISBN of a `urn:isbn:` URN, without hyphens, if its check digit is valid.
+  pub fn isbn(&self) -> Option<String> {
+    if !self.is_nid("isbn") {
+      return None;
+    }
+    let isbn = self
+      .nss
+      .chars()
+      .filter(|c| *c != '-')
+      .map(|c| c.to_ascii_uppercase())
+      .collect::<String>();
+    let digit = |c: char| c.to_digit(10);
+    let valid = match isbn.len() {
+      10 => {
+        let sum = isbn.chars().enumerate().try_fold(0, |acc, (i, c)| {
+          let v = if i == 9 && c == 'X' {
+            Some(10)
+          } else {
+            digit(c)
+          }?;
+          Some(acc + v * (10 - i as u32))
+        });
+        sum.map(|s| s % 11 == 0).unwrap_or(false)
+      }
+      13 => {
+        let sum = isbn.chars().enumerate().try_fold(0, |acc, (i, c)| {
+          Some(acc + digit(c)? * if i % 2 == 0 { 1 } else { 3 })
+        });
+        sum.map(|s| s % 10 == 0).unwrap_or(false)
+      }
+      _ => false,
+    };
+    if valid {
+      Some(isbn)
+    } else {
+      None
+    }
+  }
+}
+
+fn normalize_pct_encoded(s: &str) -> String {
+  let mut result = String::with_capacity(s.len());
+  let mut rest = 0;
+  for c in s.chars() {
+    if c == '%' {
+      rest = 2;
+      result.push(c);
+    } else if rest > 0 {
+      rest -= 1;
+      result.push(c.to_ascii_uppercase());
+    } else {
+      result.push(c);
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use crate::{SchemeError, Uri, Urn};
+
+  #[test]
+  fn test_from_uri() {
+    let uri = Uri::parse("urn:isbn:0451450523").unwrap();
+    let urn = Urn::from_uri(&uri).unwrap();
+    assert_eq!(urn.nid(), "isbn");
+    assert_eq!(urn.nss(), "0451450523");
+    assert_eq!(urn.to_string(), "urn:isbn:0451450523");
+    assert!(matches!(
+      Urn::from_uri(&Uri::parse("http://example.com").unwrap()),
+      Err(SchemeError::UnexpectedScheme { .. })
+    ));
+  }
+
+  #[test]
+  fn test_equivalence() {
+    let a = Urn::parse("urn:example:a123,z456").unwrap();
+    let b = Urn::parse("URN:EXAMPLE:a123,z456").unwrap();
+    let c = Urn::parse("urn:example:a123,z456?+abc").unwrap();
+    let d = Urn::parse("urn:example:a123,z456#789").unwrap();
+    let e = Urn::parse("urn:example:A123,z456").unwrap();
+    let f = Urn::parse("urn:example:a123%2Cz456").unwrap();
+    let g = Urn::parse("urn:example:a123%2cz456").unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a, c);
+    assert_eq!(a, d);
+    assert_ne!(a, e);
+    assert_ne!(a, f);
+    assert_eq!(f, g);
+    assert_eq!(g.normalized().to_string(), "urn:example:a123%2Cz456");
+    let set = vec![a, b, c, d, e, f, g]
+      .into_iter()
+      .collect::<HashSet<_>>();
+    assert_eq!(set.len(), 3);
+  }
+
+  #[test]
+  fn test_uuid() {
+    let urn = Urn::parse("urn:uuid:6E8BC430-9C3A-11D9-9669-0800200C9A66").unwrap();
+    assert_eq!(urn.uuid(), Some(0x6e8bc4309c3a11d996690800200c9a66));
+    assert_eq!(
+      Urn::from_uuid(0x6e8bc4309c3a11d996690800200c9a66).to_string(),
+      "urn:uuid:6e8bc430-9c3a-11d9-9669-0800200c9a66"
+    );
+    assert_eq!(Urn::parse("urn:uuid:6e8bc430-9c3a").unwrap().uuid(), None);
+    assert_eq!(Urn::parse("urn:isbn:0451450523").unwrap().uuid(), None);
+  }
+
+  #[test]
+  fn test_isbn() {
+    let isbn = |s: &str| Urn::parse(s).unwrap().isbn();
+    assert_eq!(isbn("urn:isbn:0451450523"), Some("0451450523".to_string()));
+    assert_eq!(
+      isbn("urn:isbn:0-8044-2957-x"),
+      Some("080442957X".to_string())
+    );
+    assert_eq!(
+      isbn("urn:ISBN:978-3-16-148410-0"),
+      Some("9783161484100".to_string())
+    );
+    assert_eq!(isbn("urn:isbn:0451450524"), None);
+    assert_eq!(isbn("urn:isbn:12345"), None);
+  }
+}
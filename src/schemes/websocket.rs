@@ -0,0 +1,155 @@
+use crate::ast::scheme::{KnownScheme, Scheme};
+use crate::ast::uri::Uri;
+use crate::schemes::SchemeError;
+
+/// WebSocket URIs (RFC 6455, section 3).
+impl Uri {
+  /// Converts an `http`/`https` URI to the `ws`/`wss` URI of the same resource.
+  ///
+  /// `ws` and `wss` URIs are returned as they are. The fragment is dropped, since a
+  /// WebSocket URI has none.
+  pub fn to_websocket(&self) -> Result<Uri, SchemeError> {
+    let scheme = match self.schema().known() {
+      KnownScheme::Http | KnownScheme::Ws => KnownScheme::Ws,
+      KnownScheme::Https | KnownScheme::Wss => KnownScheme::Wss,
+      _ => return Err(unexpected_scheme(KnownScheme::Http, self.schema())),
+    };
+    let uri = self.with_scheme_without_fragment(scheme);
+    uri.validate_websocket()?;
+    Ok(uri)
+  }
+
+  /// Converts a `ws`/`wss` URI to the `http`/`https` URI used for the opening handshake.
+  pub fn from_websocket(&self) -> Result<Uri, SchemeError> {
+    self.validate_websocket()?;
+    let scheme = if self.schema().known() == KnownScheme::Wss {
+      KnownScheme::Https
+    } else {
+      KnownScheme::Http
+    };
+    Ok(self.with_scheme_without_fragment(scheme))
+  }
+
+  /// Checks that this is a `ws` or `wss` URI with a host and without a fragment.
+  pub fn validate_websocket(&self) -> Result<(), SchemeError> {
+    match self.schema().known() {
+      KnownScheme::Ws | KnownScheme::Wss => {}
+      _ => return Err(unexpected_scheme(KnownScheme::Ws, self.schema())),
+    }
+    if self
+      .authority()
+      .map(|a| a.host_name().to_string().is_empty())
+      .unwrap_or(true)
+    {
+      return Err(SchemeError::InvalidSyntax(format!(
+        "a WebSocket URI needs a host: {}",
+        self
+      )));
+    }
+    if self.fragment().is_some() {
+      return Err(SchemeError::InvalidSyntax(format!(
+        "a WebSocket URI has no fragment: {}",
+        self
+      )));
+    }
+    Ok(())
+  }
+
+  pub fn is_websocket(&self) -> bool {
+    self.validate_websocket().is_ok()
+  }
+
+  /// The resource name sent in the handshake request line: the path, `/` when it is
+  /// empty, followed by the query.
+  pub fn resource_name(&self) -> String {
+    let path = self.path().to_string();
+    format!(
+      "{}{}",
+      if path.is_empty() { "/" } else { &path },
+      self
+        .query()
+        .map(|q| format!("?{}", q))
+        .unwrap_or("".to_string())
+    )
+  }
+
+  fn with_scheme_without_fragment(&self, scheme: KnownScheme) -> Uri {
+    Uri::new(
+      Scheme::from(scheme),
+      self.authority().cloned(),
+      self.path().clone(),
+      self.query().cloned(),
+      None,
+    )
+  }
+}
+
+fn unexpected_scheme(expected: KnownScheme, actual: &Scheme) -> SchemeError {
+  SchemeError::UnexpectedScheme {
+    expected,
+    actual: actual.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{SchemeError, Uri};
+
+  #[test]
+  fn test_to_websocket() {
+    let ws = |s: &str| Uri::parse(s).unwrap().to_websocket().map(|u| u.to_string());
+    assert_eq!(
+      ws("http://example.com/chat?room=1#top").unwrap(),
+      "ws://example.com/chat?room=1"
+    );
+    assert_eq!(
+      ws("HTTPS://example.com:8443").unwrap(),
+      "wss://example.com:8443"
+    );
+    assert_eq!(ws("wss://example.com/").unwrap(), "wss://example.com/");
+    assert!(matches!(
+      ws("ftp://example.com/"),
+      Err(SchemeError::UnexpectedScheme { .. })
+    ));
+    assert!(matches!(
+      ws("http:/no/host"),
+      Err(SchemeError::InvalidSyntax(_))
+    ));
+  }
+
+  #[test]
+  fn test_from_websocket() {
+    let http = |s: &str| {
+      Uri::parse(s)
+        .unwrap()
+        .from_websocket()
+        .map(|u| u.to_string())
+    };
+    assert_eq!(
+      http("ws://example.com/chat").unwrap(),
+      "http://example.com/chat"
+    );
+    assert_eq!(
+      http("wss://example.com/chat?a=b").unwrap(),
+      "https://example.com/chat?a=b"
+    );
+    assert!(matches!(
+      http("ws://example.com/chat#top"),
+      Err(SchemeError::InvalidSyntax(_))
+    ));
+    assert!(matches!(
+      http("https://example.com/"),
+      Err(SchemeError::UnexpectedScheme { .. })
+    ));
+  }
+
+  #[test]
+  fn test_resource_name() {
+    let name = |s: &str| Uri::parse(s).unwrap().resource_name();
+    assert_eq!(name("ws://example.com"), "/");
+    assert_eq!(name("ws://example.com/"), "/");
+    assert_eq!(name("ws://example.com/chat"), "/chat");
+    assert_eq!(name("ws://example.com?a=1&b"), "/?a=1&b");
+    assert_eq!(name("wss://example.com/a/b?c=d"), "/a/b?c=d");
+  }
+}